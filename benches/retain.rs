@@ -0,0 +1,69 @@
+#![feature(allocator_api, test)]
+
+extern crate test;
+
+use std::alloc::Global;
+use string_alloc::String;
+use test::{black_box, Bencher};
+
+/// About 1 MiB of log-like text with a mix of ASCII and multi-byte chars.
+fn sample() -> std::string::String {
+    "2024-01-01T00:00:00Z user=añ🦀 ip=10.0.0.1 msg=\"ok\"\n".repeat(20_000)
+}
+
+#[bench]
+fn retain_digits(b: &mut Bencher) {
+    let text = sample();
+    b.iter(|| {
+        let mut s = String::from_str_in(&text, Global);
+        s.retain(|c| !c.is_ascii_digit());
+        black_box(s)
+    });
+}
+
+#[bench]
+fn retain_digits_std(b: &mut Bencher) {
+    let text = sample();
+    b.iter(|| {
+        let mut s = text.clone();
+        s.retain(|c| !c.is_ascii_digit());
+        black_box(s)
+    });
+}
+
+#[bench]
+fn retain_ascii_digits(b: &mut Bencher) {
+    let text = sample();
+    b.iter(|| {
+        let mut s = String::from_str_in(&text, Global);
+        s.retain_ascii(|b| !b.is_ascii_digit());
+        black_box(s)
+    });
+}
+
+#[bench]
+fn retain_mut_uppercase(b: &mut Bencher) {
+    let text = sample();
+    b.iter(|| {
+        let mut s = String::from_str_in(&text, Global);
+        s.retain_mut(|c| {
+            c.make_ascii_uppercase();
+            true
+        });
+        black_box(s)
+    });
+}
+
+#[bench]
+fn retain_mut_growing(b: &mut Bencher) {
+    // 'ɐ' uppercases to 'Ɐ', which is one byte longer, so every replacement needs more room
+    let text = sample().replace('a', "ɐ");
+    b.iter(|| {
+        let mut s = String::from_str_in(&text, Global);
+        s.retain_mut(|c| {
+            *c = c.to_uppercase().next().unwrap();
+            true
+        });
+        black_box(s)
+    });
+}
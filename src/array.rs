@@ -0,0 +1,450 @@
+//! A fixed-capacity string stored entirely inline, for paths that must never allocate.
+
+use core::alloc::Allocator;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, DerefMut};
+use core::str::{self, FromStr};
+
+use crate::{CloneIn, String};
+
+/// A string with a fixed capacity of `N` bytes that lives entirely on the stack.
+///
+/// Operations that would grow past `N` return a [`CapacityError`] and leave the string unchanged instead of
+/// reallocating. Use [`format_array!`](crate::format_array) to format into one, and [`ArrayString::clone_in`] to
+/// copy it into an allocator.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use std::alloc::Global;
+/// use string_alloc::ArrayString;
+///
+/// let mut s = ArrayString::<8>::new();
+/// s.push_str("irq:").unwrap();
+/// s.push('7').unwrap();
+/// assert!(s.push_str("overflow").is_err());
+/// assert_eq!(&*s, "irq:7");
+///
+/// let heap = s.clone_in(Global);
+/// assert_eq!(&*heap, "irq:7");
+/// ```
+#[derive(Clone, Copy)]
+pub struct ArrayString<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> ArrayString<N> {
+    /// Creates a new empty `ArrayString`.
+    pub const fn new() -> Self {
+        Self { buf: [0; N], len: 0 }
+    }
+
+    /// Returns the length of this `ArrayString`, in bytes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this `ArrayString` has a length of zero.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the fixed capacity of this `ArrayString`, in bytes.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of bytes that can still be pushed.
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    /// Returns `true` if no more bytes can be pushed.
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Returns a byte slice of this `ArrayString`'s contents.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Extracts a string slice containing the entire `ArrayString`.
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Converts this `ArrayString` into a mutable string slice.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.buf[..self.len]) }
+    }
+
+    /// Appends a given string slice onto the end of this `ArrayString`.
+    ///
+    /// Returns an error, leaving the string unchanged, if the slice does not fit.
+    pub fn push_str(&mut self, s: &str) -> Result<(), CapacityError> {
+        if s.len() > self.remaining_capacity() {
+            return Err(CapacityError(()));
+        }
+        self.buf[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+
+    /// Appends the given char to the end of this `ArrayString`.
+    ///
+    /// Returns an error, leaving the string unchanged, if the char does not fit.
+    pub fn push(&mut self, ch: char) -> Result<(), CapacityError> {
+        let mut buf = [0; 4];
+        self.push_str(ch.encode_utf8(&mut buf))
+    }
+
+    /// Removes the last character and returns it.
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.len -= ch.len_utf8();
+        Some(ch)
+    }
+
+    /// Inserts a character at a byte position.
+    ///
+    /// Returns an error, leaving the string unchanged, if the char does not fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the length, or if it does not lie on a char boundary.
+    pub fn insert(&mut self, idx: usize, ch: char) -> Result<(), CapacityError> {
+        assert!(self.is_char_boundary(idx));
+        let ch_len = ch.len_utf8();
+        if ch_len > self.remaining_capacity() {
+            return Err(CapacityError(()));
+        }
+        self.buf.copy_within(idx..self.len, idx + ch_len);
+        ch.encode_utf8(&mut self.buf[idx..idx + ch_len]);
+        self.len += ch_len;
+        Ok(())
+    }
+
+    /// Removes a char at a byte position and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than or equal to the length, or if it does not lie on a char boundary.
+    pub fn remove(&mut self, idx: usize) -> char {
+        let ch = match self[idx..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string"),
+        };
+        self.buf.copy_within(idx + ch.len_utf8()..self.len, idx);
+        self.len -= ch.len_utf8();
+        ch
+    }
+
+    /// Shortens this `ArrayString` to the specified length in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a char boundary.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len <= self.len {
+            assert!(self.is_char_boundary(new_len));
+            self.len = new_len;
+        }
+    }
+
+    /// Truncates this `ArrayString`, removing all contents.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Retains only the characters specified by the predicate.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(char) -> bool,
+    {
+        retain_inline(&mut self.buf, &mut self.len, f);
+    }
+
+    /// Copies the contents into a new `String` backed by the given allocator instance.
+    pub fn clone_in<A: Allocator>(&self, alloc: A) -> String<A> {
+        String::from_str_in(self, alloc)
+    }
+}
+
+/// Retains the chars of `buf[..*len]` selected by `f`, moving the kept ones down in place.
+///
+/// Shared by the inline strings. `*len` only ever covers the chars kept so far, so a panicking predicate leaves a
+/// valid prefix behind.
+pub(crate) fn retain_inline<F>(buf: &mut [u8], len: &mut usize, mut f: F)
+where
+    F: FnMut(char) -> bool,
+{
+    let old_len = core::mem::replace(len, 0);
+    let mut idx = 0;
+    while idx < old_len {
+        // Kept chars only ever move down, so the bytes from `idx` on are still the original UTF-8
+        let ch = unsafe { str::from_utf8_unchecked(&buf[idx..old_len]) }
+            .chars()
+            .next()
+            .unwrap();
+        let ch_len = ch.len_utf8();
+        if f(ch) {
+            buf.copy_within(idx..idx + ch_len, *len);
+            *len += ch_len;
+        }
+        idx += ch_len;
+    }
+}
+
+/// The error returned when an [`ArrayString`] operation would exceed its fixed capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError(());
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
+impl core::error::Error for CapacityError {}
+
+impl<const N: usize> Default for ArrayString<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for ArrayString<N> {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> DerefMut for ArrayString<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Display for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Write for ArrayString<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<ArrayString<M>> for ArrayString<N> {
+    fn eq(&self, other: &ArrayString<M>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for ArrayString<N> {}
+
+impl<const N: usize> PartialEq<str> for ArrayString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ArrayString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize, A: Allocator> PartialEq<String<A>> for ArrayString<N> {
+    fn eq(&self, other: &String<A>) -> bool {
+        self.as_str() == &**other
+    }
+}
+
+impl<const N: usize> PartialOrd for ArrayString<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Ord for ArrayString<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<const N: usize> Hash for ArrayString<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl<const N: usize> AsRef<str> for ArrayString<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for ArrayString<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize> Borrow<str> for ArrayString<N> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> FromStr for ArrayString<N> {
+    type Err = CapacityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut res = Self::new();
+        res.push_str(s)?;
+        Ok(res)
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for ArrayString<N> {
+    type Error = CapacityError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl<const N: usize, A: Allocator + Default> From<ArrayString<N>> for String<A> {
+    fn from(s: ArrayString<N>) -> Self {
+        s.clone_in(A::default())
+    }
+}
+
+impl<const N: usize, B: Allocator> CloneIn<B> for ArrayString<N> {
+    type Cloned = String<B>;
+
+    fn clone_in(&self, alloc: B) -> Self::Cloned {
+        String::from_str_in(self, alloc)
+    }
+}
+
+/// Formats the arguments into a new [`ArrayString`] with capacity `N`, without allocating.
+///
+/// Returns a [`CapacityError`] if the output does not fit.
+///
+/// # Panics
+///
+/// Panics if a formatting trait implementation returns an error on its own.
+///
+/// # Examples
+///
+/// ```
+/// use string_alloc::format_array;
+///
+/// let s = format_array!(16, "irq {} fired", 7).unwrap();
+/// assert_eq!(&*s, "irq 7 fired");
+/// assert!(format_array!(4, "{}", 123456).is_err());
+/// ```
+#[macro_export]
+macro_rules! format_array {
+    ($cap:expr, $($arg:tt)*) => {
+        $crate::array::__format_array::<{ $cap }>(::core::format_args!($($arg)*))
+    };
+}
+
+#[doc(hidden)]
+pub fn __format_array<const N: usize>(args: fmt::Arguments<'_>) -> Result<ArrayString<N>, CapacityError> {
+    let mut s = ArrayString::new();
+    let mut writer = TryPush {
+        s: &mut s,
+        overflowed: false,
+    };
+    if fmt::write(&mut writer, args).is_err() {
+        if writer.overflowed {
+            return Err(CapacityError(()));
+        }
+        panic!("a formatting trait implementation returned an error when the underlying stream did not");
+    }
+    Ok(s)
+}
+
+/// Writer used by `format_array!`, remembering whether the write failed for lack of space.
+struct TryPush<'a, const N: usize> {
+    s: &'a mut ArrayString<N>,
+    overflowed: bool,
+}
+
+impl<const N: usize> fmt::Write for TryPush<'_, N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.s.push_str(s).map_err(|_| {
+            self.overflowed = true;
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for ArrayString<N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for ArrayString<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(ArrayStringVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct ArrayStringVisitor<const N: usize>;
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::de::Visitor<'de> for ArrayStringVisitor<N> {
+    type Value = ArrayString<N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a string of at most {} bytes", N)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        v.parse().map_err(|_| E::invalid_length(v.len(), &self))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match str::from_utf8(v) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(E::invalid_value(serde::de::Unexpected::Bytes(v), &self)),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Deep copies of string-holding data structures into a new allocator.
+
+use alloc::vec::Vec;
+use core::alloc::Allocator;
+
+use crate::String;
+
+/// Clones a value into a given allocator instance.
+///
+/// This is implemented for [`String`] and for the common containers around it, so a whole data structure can be
+/// deep-copied into a new allocator in one call:
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use std::alloc::Global;
+/// use string_alloc::{CloneIn, String};
+///
+/// let names = (Some(String::from_str_in("a", Global)), [String::from_str_in("b", Global)]);
+/// let copy: (Option<String<Global>>, [String<Global>; 1]) = names.clone_in(Global);
+/// assert_eq!(&*copy.1[0], "b");
+/// ```
+pub trait CloneIn<B: Allocator> {
+    /// The type of the copy, with every string moved over to `B`.
+    type Cloned;
+
+    /// Returns a deep copy of `self` with all allocations made in `alloc`.
+    fn clone_in(&self, alloc: B) -> Self::Cloned;
+}
+
+impl<A: Allocator, B: Allocator> CloneIn<B> for String<A> {
+    type Cloned = String<B>;
+
+    fn clone_in(&self, alloc: B) -> Self::Cloned {
+        String::from_str_in(self, alloc)
+    }
+}
+
+impl<T: CloneIn<B>, B: Allocator> CloneIn<B> for Option<T> {
+    type Cloned = Option<T::Cloned>;
+
+    fn clone_in(&self, alloc: B) -> Self::Cloned {
+        self.as_ref().map(|v| v.clone_in(alloc))
+    }
+}
+
+impl<T: CloneIn<B>, B: Allocator + Clone, const N: usize> CloneIn<B> for [T; N] {
+    type Cloned = [T::Cloned; N];
+
+    fn clone_in(&self, alloc: B) -> Self::Cloned {
+        core::array::from_fn(|i| self[i].clone_in(alloc.clone()))
+    }
+}
+
+impl<T: CloneIn<B>, A: Allocator, B: Allocator + Clone> CloneIn<B> for Vec<T, A> {
+    type Cloned = Vec<T::Cloned, B>;
+
+    fn clone_in(&self, alloc: B) -> Self::Cloned {
+        let mut vec = Vec::with_capacity_in(self.len(), alloc.clone());
+        vec.extend(self.iter().map(|v| v.clone_in(alloc.clone())));
+        vec
+    }
+}
+
+macro_rules! tuple_clone_in {
+    ($($name:ident)+) => {
+        impl<B: Allocator + Clone, $($name: CloneIn<B>),+> CloneIn<B> for ($($name,)+) {
+            type Cloned = ($($name::Cloned,)+);
+
+            #[allow(non_snake_case)]
+            fn clone_in(&self, alloc: B) -> Self::Cloned {
+                let ($($name,)+) = self;
+                ($($name.clone_in(alloc.clone()),)+)
+            }
+        }
+    };
+}
+
+tuple_clone_in!(T1);
+tuple_clone_in!(T1 T2);
+tuple_clone_in!(T1 T2 T3);
+tuple_clone_in!(T1 T2 T3 T4);
+tuple_clone_in!(T1 T2 T3 T4 T5);
+tuple_clone_in!(T1 T2 T3 T4 T5 T6);
+tuple_clone_in!(T1 T2 T3 T4 T5 T6 T7);
+tuple_clone_in!(T1 T2 T3 T4 T5 T6 T7 T8);
@@ -0,0 +1,224 @@
+//! A borrowed-or-owned string whose owned form is an allocator-aware [`String`].
+
+use core::alloc::Allocator;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+use ::alloc::alloc::Global;
+
+use crate::String;
+
+/// A clone-on-write string slice that owns its data in allocator `A` once modified.
+///
+/// `alloc::borrow::Cow<'a, str>` is tied to `std::string::String` through `ToOwned`, so it cannot hold a
+/// [`String<A>`]. `CowStr` fills that gap, e.g. for parsers that return borrowed slices when no unescaping is
+/// needed and arena-owned strings otherwise.
+///
+/// See [`alloc::borrow::Cow`] for more details.
+pub enum CowStr<'a, A: Allocator = Global> {
+    /// Borrowed data.
+    Borrowed(&'a str),
+    /// Owned data.
+    Owned(String<A>),
+}
+
+impl<'a, A: Allocator> CowStr<'a, A> {
+    /// Returns `true` if the data is borrowed.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, CowStr::Borrowed(_))
+    }
+
+    /// Returns `true` if the data is owned.
+    pub fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+
+    /// Extracts the string slice.
+    pub fn as_str(&self) -> &str {
+        match self {
+            CowStr::Borrowed(s) => s,
+            CowStr::Owned(s) => s,
+        }
+    }
+
+    /// Acquires a mutable reference to the owned form, copying borrowed data into `A::default()` first.
+    ///
+    /// See [`alloc::borrow::Cow::to_mut`] for more details.
+    pub fn to_mut(&mut self) -> &mut String<A>
+    where
+        A: Default,
+    {
+        self.to_mut_in(A::default())
+    }
+
+    /// Acquires a mutable reference to the owned form, copying borrowed data into `alloc` first.
+    ///
+    /// `alloc` is dropped unused if the data is already owned.
+    pub fn to_mut_in(&mut self, alloc: A) -> &mut String<A> {
+        if let CowStr::Borrowed(s) = *self {
+            *self = CowStr::Owned(String::from_str_in(s, alloc));
+        }
+        match self {
+            CowStr::Borrowed(_) => unreachable!(),
+            CowStr::Owned(s) => s,
+        }
+    }
+
+    /// Extracts the owned data, copying borrowed data into `A::default()`.
+    ///
+    /// See [`alloc::borrow::Cow::into_owned`] for more details.
+    pub fn into_owned(self) -> String<A>
+    where
+        A: Default,
+    {
+        self.into_owned_in(A::default())
+    }
+
+    /// Extracts the owned data, copying borrowed data into `alloc`.
+    ///
+    /// `alloc` is dropped unused if the data is already owned.
+    pub fn into_owned_in(self, alloc: A) -> String<A> {
+        match self {
+            CowStr::Borrowed(s) => String::from_str_in(s, alloc),
+            CowStr::Owned(s) => s,
+        }
+    }
+}
+
+impl<A: Allocator> Deref for CowStr<'_, A> {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<A: Allocator + Clone> Clone for CowStr<'_, A> {
+    fn clone(&self) -> Self {
+        match self {
+            CowStr::Borrowed(s) => CowStr::Borrowed(s),
+            CowStr::Owned(s) => CowStr::Owned(s.clone()),
+        }
+    }
+}
+
+impl<A: Allocator> Default for CowStr<'_, A> {
+    fn default() -> Self {
+        CowStr::Borrowed("")
+    }
+}
+
+impl<A: Allocator> fmt::Debug for CowStr<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<A: Allocator> fmt::Display for CowStr<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<A: Allocator, B: Allocator> PartialEq<CowStr<'_, B>> for CowStr<'_, A> {
+    fn eq(&self, other: &CowStr<'_, B>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<A: Allocator> Eq for CowStr<'_, A> {}
+
+impl<A: Allocator> PartialEq<str> for CowStr<'_, A> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<A: Allocator> PartialEq<&str> for CowStr<'_, A> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<A: Allocator, B: Allocator> PartialEq<String<B>> for CowStr<'_, A> {
+    fn eq(&self, other: &String<B>) -> bool {
+        self.as_str() == &**other
+    }
+}
+
+impl<A: Allocator> PartialOrd for CowStr<'_, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<A: Allocator> Ord for CowStr<'_, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<A: Allocator> Hash for CowStr<'_, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl<A: Allocator> AsRef<str> for CowStr<'_, A> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<A: Allocator> AsRef<[u8]> for CowStr<'_, A> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<A: Allocator> Borrow<str> for CowStr<'_, A> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a, A: Allocator> From<&'a str> for CowStr<'a, A> {
+    fn from(s: &'a str) -> Self {
+        CowStr::Borrowed(s)
+    }
+}
+
+impl<A: Allocator> From<String<A>> for CowStr<'_, A> {
+    fn from(s: String<A>) -> Self {
+        CowStr::Owned(s)
+    }
+}
+
+impl<'a, A: Allocator> From<&'a String<A>> for CowStr<'a, A> {
+    fn from(s: &'a String<A>) -> Self {
+        CowStr::Borrowed(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<A: Allocator> serde::Serialize for CowStr<'_, A> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, A: Allocator + Default> serde::Deserialize<'de> for CowStr<'de, A> {
+    /// Borrows from the input when the format allows it, and copies into `A::default()` otherwise.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        serde::de::DeserializeSeed::deserialize(crate::de::CowStrSeed(A::default()), deserializer)
+    }
+}
@@ -0,0 +1,436 @@
+//! Serde helpers for deserializing into a specific allocator instance.
+//!
+//! It also hosts [`boxed_str`], a `#[serde(with = ...)]` module for `Box<str, A>` fields.
+//!
+//! The [`serde::Deserialize`] impl for [`String`] always builds its allocator with `A::default()`. The seeds in
+//! this module carry an allocator handle instead, so stateful allocators such as arenas can be filled directly
+//! from a deserializer:
+//!
+//! ```
+//! #![feature(allocator_api)]
+//!
+//! use serde::de::DeserializeSeed;
+//! use std::alloc::Global;
+//! use string_alloc::de::VecSeed;
+//!
+//! let mut de = serde_json::Deserializer::from_str(r#"["a", "b"]"#);
+//! let names = VecSeed(Global).deserialize(&mut de).unwrap();
+//! assert_eq!(&*names[1], "b");
+//! ```
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::alloc::Allocator;
+use core::fmt;
+use core::str;
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Unexpected, Visitor};
+
+use crate::{CowStr, Interner, SmallString, String};
+
+/// Deserializes a [`String`] into the wrapped allocator.
+#[derive(Debug, Clone, Copy)]
+pub struct StringSeed<A>(pub A);
+
+impl<'de, A: Allocator> DeserializeSeed<'de> for StringSeed<A> {
+    type Value = String<A>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_string(StringVisitor(self.0))
+    }
+}
+
+/// Deserializes a [`CowStr`], borrowing from the input when the format allows it and copying into the wrapped
+/// allocator otherwise.
+#[derive(Debug, Clone, Copy)]
+pub struct CowStrSeed<A>(pub A);
+
+impl<'de, A: Allocator> DeserializeSeed<'de> for CowStrSeed<A> {
+    type Value = CowStr<'de, A>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(CowStrVisitor(self.0))
+    }
+}
+
+/// Deserializes a [`SmallString`], spilling into the wrapped allocator only if the string does not fit inline.
+#[derive(Debug, Clone, Copy)]
+pub struct SmallStringSeed<const N: usize, A>(pub A);
+
+impl<'de, const N: usize, A: Allocator> DeserializeSeed<'de> for SmallStringSeed<N, A> {
+    type Value = SmallString<N, A>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(SmallStringVisitor(self.0))
+    }
+}
+
+/// Deserializes a `Box<str, A>` into the wrapped allocator.
+#[derive(Debug, Clone, Copy)]
+pub struct BoxStrSeed<A>(pub A);
+
+impl<'de, A: Allocator> DeserializeSeed<'de> for BoxStrSeed<A> {
+    type Value = Box<str, A>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        StringSeed(self.0).deserialize(deserializer).map(String::into_boxed_str)
+    }
+}
+
+/// Deserializes a sequence of strings into a `Vec<String<A>, A>`, with the vector and every element sharing the
+/// wrapped allocator.
+#[derive(Debug, Clone, Copy)]
+pub struct VecSeed<A>(pub A);
+
+impl<'de, A: Allocator + Clone> DeserializeSeed<'de> for VecSeed<A> {
+    type Value = Vec<String<A>, A>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(VecVisitor(self.0))
+    }
+}
+
+/// Deserializes a map of strings into a `BTreeMap<String<A>, String<A>, A>`, with the map and every key and value
+/// sharing the wrapped allocator.
+#[derive(Debug, Clone, Copy)]
+pub struct MapSeed<A>(pub A);
+
+impl<'de, A: Allocator + Clone> DeserializeSeed<'de> for MapSeed<A> {
+    type Value = BTreeMap<String<A>, String<A>, A>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(MapVisitor(self.0))
+    }
+}
+
+/// Deserializes a sequence of distinct strings into an [`Interner<A>`], assigning symbols in sequence order.
+///
+/// Duplicate strings are rejected, since they would shift every later symbol.
+#[derive(Debug, Clone, Copy)]
+pub struct InternerSeed<A>(pub A);
+
+impl<'de, A: Allocator + Clone> DeserializeSeed<'de> for InternerSeed<A> {
+    type Value = Interner<A>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(InternerVisitor(self.0))
+    }
+}
+
+/// Builds a `String` in the carried allocator from any string or UTF-8 bytes the deserializer produces.
+struct StringVisitor<A>(A);
+
+impl<'de, A: Allocator> Visitor<'de> for StringVisitor<A> {
+    type Value = String<A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(String::from_str_in(v, self.0))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(v)
+    }
+
+    fn visit_string<E>(self, v: alloc::string::String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(&v)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match str::from_utf8(v) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_bytes(v)
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_bytes(&v)
+    }
+}
+
+/// (De)serializes a `Box<str, A>` field, for use with `#[serde(with = "string_alloc::de::boxed_str")]`.
+///
+/// Serde only implements its traits for `Box<str>` in the global allocator.
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use serde::{Deserialize, Serialize};
+/// use std::alloc::Global;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Frozen {
+///     #[serde(with = "string_alloc::de::boxed_str")]
+///     name: Box<str, Global>,
+/// }
+///
+/// let frozen: Frozen = serde_json::from_str(r#"{"name": "ice"}"#).unwrap();
+/// assert_eq!(&*frozen.name, "ice");
+/// assert_eq!(serde_json::to_string(&frozen).unwrap(), r#"{"name":"ice"}"#);
+/// ```
+pub mod boxed_str {
+    use alloc::boxed::Box;
+    use core::alloc::Allocator;
+
+    use serde::de::DeserializeSeed;
+    use serde::{Deserializer, Serializer};
+
+    use super::BoxStrSeed;
+
+    /// Serializes a `Box<str, A>` as a string.
+    #[allow(clippy::borrowed_box)] // Serde passes `&T` for the field type
+    pub fn serialize<A, S>(value: &Box<str, A>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        A: Allocator,
+        S: Serializer,
+    {
+        serializer.serialize_str(value)
+    }
+
+    /// Deserializes a `Box<str, A>` into `A::default()`.
+    pub fn deserialize<'de, A, D>(deserializer: D) -> Result<Box<str, A>, D::Error>
+    where
+        A: Allocator + Default,
+        D: Deserializer<'de>,
+    {
+        BoxStrSeed(A::default()).deserialize(deserializer)
+    }
+}
+
+struct CowStrVisitor<A>(A);
+
+impl<'de, A: Allocator> Visitor<'de> for CowStrVisitor<A> {
+    type Value = CowStr<'de, A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(CowStr::Borrowed(v))
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match str::from_utf8(v) {
+            Ok(s) => Ok(CowStr::Borrowed(s)),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        StringVisitor(self.0).visit_str(v).map(CowStr::Owned)
+    }
+
+    fn visit_string<E>(self, v: alloc::string::String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        StringVisitor(self.0).visit_string(v).map(CowStr::Owned)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        StringVisitor(self.0).visit_bytes(v).map(CowStr::Owned)
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        StringVisitor(self.0).visit_byte_buf(v).map(CowStr::Owned)
+    }
+}
+
+struct SmallStringVisitor<const N: usize, A>(A);
+
+impl<'de, const N: usize, A: Allocator> Visitor<'de> for SmallStringVisitor<N, A> {
+    type Value = SmallString<N, A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    // Serde forwards the borrowed and owned variants to these two by default
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(SmallString::from_str_in(v, self.0))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match str::from_utf8(v) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+struct VecVisitor<A>(A);
+
+impl<'de, A: Allocator + Clone> Visitor<'de> for VecVisitor<A> {
+    type Value = Vec<String<A>, A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of strings")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        // Cap the size hint like serde does, so a hostile length can't force a huge allocation up front
+        let cap = seq.size_hint().unwrap_or(0).min(4096);
+        let mut vec = Vec::with_capacity_in(cap, self.0.clone());
+        while let Some(s) = seq.next_element_seed(StringSeed(self.0.clone()))? {
+            vec.push(s);
+        }
+        Ok(vec)
+    }
+}
+
+struct MapVisitor<A>(A);
+
+impl<'de, A: Allocator + Clone> Visitor<'de> for MapVisitor<A> {
+    type Value = BTreeMap<String<A>, String<A>, A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of strings")
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut res = BTreeMap::new_in(self.0.clone());
+        while let Some((k, v)) = map.next_entry_seed(StringSeed(self.0.clone()), StringSeed(self.0.clone()))? {
+            res.insert(k, v);
+        }
+        Ok(res)
+    }
+}
+
+struct InternerVisitor<A>(A);
+
+impl<'de, A: Allocator + Clone> Visitor<'de> for InternerVisitor<A> {
+    type Value = Interner<A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of distinct strings")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        let cap = seq.size_hint().unwrap_or(0).min(4096);
+        let mut interner = Interner::with_capacity_in(cap, self.0);
+        while seq.next_element_seed(InternSeed(&mut interner))?.is_some() {}
+        Ok(interner)
+    }
+}
+
+/// Interns one string straight from the deserializer, without an intermediate allocation.
+struct InternSeed<'a, A: Allocator + Clone>(&'a mut Interner<A>);
+
+impl<'de, A: Allocator + Clone> DeserializeSeed<'de> for InternSeed<'_, A> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, A: Allocator + Clone> Visitor<'de> for InternSeed<'_, A> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string not seen before")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if self.0.get(v).is_some() {
+            return Err(E::invalid_value(Unexpected::Str(v), &self));
+        }
+        self.0.get_or_intern(v);
+        Ok(())
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match str::from_utf8(v) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+}
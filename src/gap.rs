@@ -0,0 +1,361 @@
+//! A gap-buffer string for edits that cluster around a cursor.
+//!
+//! [`GapString`] keeps its free capacity as a gap in the middle of the buffer. Typing and deleting at the cursor
+//! only touches the gap's edges, so they are amortized `O(1)`; moving the gap costs time proportional to the
+//! distance moved, and only happens when the next edit needs it.
+//!
+//! ```
+//! #![feature(allocator_api)]
+//!
+//! use std::alloc::Global;
+//! use string_alloc::GapString;
+//!
+//! let mut line = GapString::from_str_in("let x = ;", Global);
+//! line.move_cursor(8);
+//! line.insert_str("42");
+//! assert_eq!(line.delete_backward(), Some('2'));
+//! line.insert('7');
+//! assert_eq!(line.as_str(), "let x = 47;");
+//! ```
+
+use alloc::alloc::Global;
+use alloc::collections::TryReserveError;
+use alloc::vec::Vec;
+use core::alloc::Allocator;
+use core::{fmt, str};
+
+use crate::{rope, String};
+
+/// A UTF-8 string with a movable gap, backed by a `Vec<u8, A>`.
+///
+/// The text is `buf[..gap_start]` followed by `buf[gap_end..]`, and the gap always sits on a char boundary, so both
+/// halves are valid UTF-8 on their own. The cursor is tracked separately: the gap moves to it on the next edit.
+pub struct GapString<A: Allocator = Global> {
+    buf: Vec<u8, A>,
+    gap_start: usize,
+    gap_end: usize,
+    cursor: usize,
+}
+
+impl<A: Allocator> GapString<A> {
+    /// Creates a new empty `GapString` with the cursor at 0. It does not allocate until text is inserted.
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+
+    /// Creates a new empty `GapString` with a gap of at least `capacity` bytes.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        let mut buf = Vec::with_capacity_in(capacity, alloc);
+        buf.resize(buf.capacity(), 0);
+        GapString {
+            gap_start: 0,
+            gap_end: buf.len(),
+            cursor: 0,
+            buf,
+        }
+    }
+
+    /// Creates a new `GapString` from a string slice, with the cursor at the end.
+    pub fn from_str_in(s: &str, alloc: A) -> Self {
+        let mut res = Self::with_capacity_in(s.len(), alloc);
+        res.insert_str(s);
+        res
+    }
+
+    /// Returns a reference to the underlying allocator.
+    pub fn allocator(&self) -> &A {
+        self.buf.allocator()
+    }
+
+    /// Returns the length of the text, in bytes.
+    pub fn len(&self) -> usize {
+        self.buf.len() - self.gap_len()
+    }
+
+    /// Returns `true` if the text has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of bytes the text can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the cursor's byte offset into the text.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns `true` if the byte at text offset `idx` is the first byte of a char, or `idx` is the length.
+    pub fn is_char_boundary(&self, idx: usize) -> bool {
+        match idx.cmp(&self.len()) {
+            core::cmp::Ordering::Less => {
+                let phys = if idx < self.gap_start {
+                    idx
+                } else {
+                    idx + self.gap_len()
+                };
+                // Not a UTF-8 continuation byte
+                (self.buf[phys] as i8) >= -0x40
+            }
+            core::cmp::Ordering::Equal => true,
+            core::cmp::Ordering::Greater => false,
+        }
+    }
+
+    /// Moves the cursor to a byte offset. This is `O(1)`: the gap follows on the next edit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the length or does not lie on a char boundary.
+    pub fn move_cursor(&mut self, idx: usize) {
+        assert!(self.is_char_boundary(idx), "cursor index is not a char boundary");
+        self.cursor = idx;
+    }
+
+    /// Moves the cursor to a char offset, counting chars from the start of the text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is larger than the number of chars in the text.
+    pub fn move_cursor_to_char(&mut self, char_idx: usize) {
+        let (front, back) = self.as_slices();
+        let idx = front
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(back.char_indices().map(|(i, _)| front.len() + i))
+            .chain(core::iter::once(self.len()))
+            .nth(char_idx)
+            .unwrap_or_else(|| panic!("char index (is {}) should be <= number of chars", char_idx));
+        self.cursor = idx;
+    }
+
+    /// Moves the cursor one char to the left, returning `false` if it is already at the start.
+    pub fn move_left(&mut self) -> bool {
+        match self.char_before(self.cursor) {
+            Some(ch) => {
+                self.cursor -= ch.len_utf8();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the cursor one char to the right, returning `false` if it is already at the end.
+    pub fn move_right(&mut self) -> bool {
+        match self.char_after(self.cursor) {
+            Some(ch) => {
+                self.cursor += ch.len_utf8();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Inserts a char at the cursor, leaving the cursor after it.
+    pub fn insert(&mut self, ch: char) {
+        let mut buf = [0; 4];
+        self.insert_str(ch.encode_utf8(&mut buf));
+    }
+
+    /// Inserts a string slice at the cursor, leaving the cursor after it.
+    pub fn insert_str(&mut self, s: &str) {
+        self.reserve(s.len());
+        self.insert_in_gap(s);
+    }
+
+    /// Tries to insert a string slice at the cursor, leaving the cursor after it.
+    ///
+    /// Returns an error instead of aborting if the allocation fails, leaving the text unchanged.
+    pub fn try_insert_str(&mut self, s: &str) -> Result<(), TryReserveError> {
+        self.try_reserve(s.len())?;
+        self.insert_in_gap(s);
+        Ok(())
+    }
+
+    /// Removes and returns the char before the cursor, like a backspace key.
+    pub fn delete_backward(&mut self) -> Option<char> {
+        self.move_gap(self.cursor);
+        let ch = self.char_before(self.cursor)?;
+        self.gap_start -= ch.len_utf8();
+        self.cursor = self.gap_start;
+        Some(ch)
+    }
+
+    /// Removes and returns the char after the cursor, like a delete key.
+    pub fn delete_forward(&mut self) -> Option<char> {
+        self.move_gap(self.cursor);
+        let ch = self.char_after(self.cursor)?;
+        self.gap_end += ch.len_utf8();
+        Some(ch)
+    }
+
+    /// Ensures the gap can hold at least `additional` more bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        if self.gap_len() < additional {
+            let old_len = self.buf.len();
+            self.buf.reserve(additional - self.gap_len());
+            self.grow_gap(old_len);
+        }
+    }
+
+    /// Tries to ensure the gap can hold at least `additional` more bytes.
+    ///
+    /// Returns an error instead of aborting if the allocation fails.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if self.gap_len() < additional {
+            let old_len = self.buf.len();
+            self.buf.try_reserve(additional - self.gap_len())?;
+            self.grow_gap(old_len);
+        }
+        Ok(())
+    }
+
+    /// Removes all text, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.gap_start = 0;
+        self.gap_end = self.buf.len();
+        self.cursor = 0;
+    }
+
+    /// Returns the text before and after the gap. This never moves the gap.
+    pub fn as_slices(&self) -> (&str, &str) {
+        // SAFETY: the gap always sits on a char boundary of UTF-8 text
+        unsafe {
+            (
+                str::from_utf8_unchecked(&self.buf[..self.gap_start]),
+                str::from_utf8_unchecked(&self.buf[self.gap_end..]),
+            )
+        }
+    }
+
+    /// Returns the text as a single slice, moving the gap to the end first if needed. The cursor does not move.
+    pub fn as_str(&mut self) -> &str {
+        let len = self.len();
+        self.move_gap(len);
+        self.as_slices().0
+    }
+
+    /// Converts this `GapString` into a [`String<A>`], reusing its buffer.
+    pub fn into_string(mut self) -> String<A> {
+        let len = self.len();
+        self.move_gap(len);
+        self.buf.truncate(len);
+        // SAFETY: with the gap at the end, `buf[..len]` is the text
+        unsafe { String::from_utf8_unchecked_in(self.buf) }
+    }
+
+    fn gap_len(&self) -> usize {
+        self.gap_end - self.gap_start
+    }
+
+    /// Extends the buffer to its new capacity and moves the text after the gap to the end.
+    fn grow_gap(&mut self, old_len: usize) {
+        self.buf.resize(self.buf.capacity(), 0);
+        let tail = old_len - self.gap_end;
+        let new_end = self.buf.len() - tail;
+        self.buf.copy_within(self.gap_end..old_len, new_end);
+        self.gap_end = new_end;
+    }
+
+    /// Moves the gap so it starts at text offset `idx`, which must be a char boundary.
+    fn move_gap(&mut self, idx: usize) {
+        if idx < self.gap_start {
+            let moved = self.gap_start - idx;
+            self.buf.copy_within(idx..self.gap_start, self.gap_end - moved);
+            self.gap_start = idx;
+            self.gap_end -= moved;
+        } else if idx > self.gap_start {
+            let moved = idx - self.gap_start;
+            self.buf.copy_within(self.gap_end..self.gap_end + moved, self.gap_start);
+            self.gap_start = idx;
+            self.gap_end += moved;
+        }
+    }
+
+    /// Copies `s` into the gap at the cursor. The gap must already have room for it.
+    fn insert_in_gap(&mut self, s: &str) {
+        self.move_gap(self.cursor);
+        self.buf[self.gap_start..self.gap_start + s.len()].copy_from_slice(s.as_bytes());
+        self.gap_start += s.len();
+        self.cursor = self.gap_start;
+    }
+
+    fn char_before(&self, idx: usize) -> Option<char> {
+        let (front, back) = self.as_slices();
+        match idx.checked_sub(front.len()) {
+            None => front[..idx].chars().next_back(),
+            Some(i) => back[..i].chars().next_back().or_else(|| front.chars().next_back()),
+        }
+    }
+
+    fn char_after(&self, idx: usize) -> Option<char> {
+        let (front, back) = self.as_slices();
+        match idx.checked_sub(front.len()) {
+            None => front[idx..].chars().next(),
+            Some(i) => back[i..].chars().next(),
+        }
+    }
+}
+
+impl<A: Allocator + Default> Default for GapString<A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<A: Allocator> fmt::Display for GapString<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (front, back) = self.as_slices();
+        rope::pad_chunks(f, [front, back].into_iter())
+    }
+}
+
+impl<A: Allocator> fmt::Debug for GapString<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (front, back) = self.as_slices();
+        rope::debug_chunks(f, [front, back].into_iter())
+    }
+}
+
+impl<A: Allocator> fmt::Write for GapString<A> {
+    /// Inserts at the cursor. Allocation failure is reported as [`fmt::Error`].
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_insert_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl<A: Allocator> PartialEq<str> for GapString<A> {
+    fn eq(&self, other: &str) -> bool {
+        let (front, back) = self.as_slices();
+        self.len() == other.len() && other.as_bytes().starts_with(front.as_bytes()) && other.ends_with(back)
+    }
+}
+
+impl<A: Allocator> PartialEq<&str> for GapString<A> {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl<A: Allocator> From<String<A>> for GapString<A> {
+    /// Reuses the string's buffer, turning its spare capacity into the gap. The cursor is placed at the end.
+    fn from(s: String<A>) -> Self {
+        let mut buf = s.into_bytes();
+        let len = buf.len();
+        buf.resize(buf.capacity(), 0);
+        GapString {
+            gap_start: len,
+            gap_end: buf.len(),
+            cursor: len,
+            buf,
+        }
+    }
+}
+
+impl<A: Allocator> From<GapString<A>> for String<A> {
+    fn from(s: GapString<A>) -> Self {
+        s.into_string()
+    }
+}
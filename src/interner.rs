@@ -0,0 +1,421 @@
+//! A string interner that hands out compact [`Symbol`] handles.
+//!
+//! Interned bytes are packed into a few large chunks in the interner's allocator, so each entry costs a 12-byte span
+//! plus an 8-byte hash table slot rather than a separate allocation. Lookups use an open-addressing table keyed by
+//! an FxHash-style hash, which is fast on the short identifiers interners usually see but not DoS-resistant.
+//!
+//! ```
+//! #![feature(allocator_api)]
+//!
+//! use std::alloc::Global;
+//! use string_alloc::Interner;
+//!
+//! let mut interner = Interner::new_in(Global);
+//! let a = interner.get_or_intern("foo");
+//! let b = interner.get_or_intern("bar");
+//! assert_eq!(interner.get_or_intern("foo"), a);
+//! assert_eq!(interner.resolve(b), "bar");
+//! assert_eq!(interner.get("baz"), None);
+//! ```
+
+use alloc::alloc::Global;
+use alloc::collections::TryReserveError;
+use alloc::vec::Vec;
+use core::alloc::Allocator;
+use core::iter::{Enumerate, FusedIterator};
+use core::{fmt, slice, str};
+
+/// Size of the first byte chunk. Later chunks double in size up to [`MAX_CHUNK`].
+const MIN_CHUNK: usize = 4 * 1024;
+const MAX_CHUNK: usize = 1024 * 1024;
+
+/// Marks an unused hash table slot. Never a valid symbol, since symbols are capped below it.
+const EMPTY: u32 = u32::MAX;
+
+/// A handle to a string stored in an [`Interner`].
+///
+/// Symbols are dense indices in interning order, starting at 0. Resolving a symbol against an interner other than
+/// the one that produced it returns an unrelated string or panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Creates a symbol from its raw index.
+    pub const fn from_u32(index: u32) -> Self {
+        Symbol(index)
+    }
+
+    /// Returns the raw index of this symbol.
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+/// Location of an interned string within the chunks.
+#[derive(Clone, Copy)]
+struct Span {
+    chunk: u32,
+    start: u32,
+    len: u32,
+}
+
+#[derive(Clone, Copy)]
+struct Slot {
+    sym: u32,
+    hash: u32,
+}
+
+const EMPTY_SLOT: Slot = Slot { sym: EMPTY, hash: 0 };
+
+/// Deduplicates strings, storing each distinct one once and identifying it by a [`Symbol`].
+///
+/// Strings are never removed; the interner grows until it is dropped.
+#[derive(Clone)]
+pub struct Interner<A: Allocator + Clone = Global> {
+    /// Byte storage. Only the last chunk is appended to, and chunks never reallocate.
+    chunks: Vec<Vec<u8, A>, A>,
+    /// Indexed by symbol.
+    spans: Vec<Span, A>,
+    /// Open-addressing hash table with linear probing. Its length is zero or a power of two.
+    table: Vec<Slot, A>,
+}
+
+impl<A: Allocator + Clone> Interner<A> {
+    /// Creates a new empty interner. It does not allocate until the first string is interned.
+    pub fn new_in(alloc: A) -> Self {
+        Interner {
+            chunks: Vec::new_in(alloc.clone()),
+            spans: Vec::new_in(alloc.clone()),
+            table: Vec::new_in(alloc),
+        }
+    }
+
+    /// Creates a new empty interner with room for at least `symbols` strings before the hash table grows.
+    pub fn with_capacity_in(symbols: usize, alloc: A) -> Self {
+        let mut res = Self::new_in(alloc);
+        if symbols > 0 {
+            res.spans.reserve(symbols);
+            let len = table_len_for(symbols);
+            res.rehash(Vec::with_capacity_in(len, res.allocator().clone()), len);
+        }
+        res
+    }
+
+    /// Returns a reference to the underlying allocator.
+    pub fn allocator(&self) -> &A {
+        self.spans.allocator()
+    }
+
+    /// Returns the number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns `true` if no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns the symbol for `s` if it has already been interned.
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.find(s, hash_str(s)).ok()
+    }
+
+    /// Returns the symbol for `s`, interning it first if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` is longer than `u32::MAX` bytes or if `u32::MAX` strings have already been interned.
+    pub fn get_or_intern(&mut self, s: &str) -> Symbol {
+        let hash = hash_str(s);
+        if let Ok(sym) = self.find(s, hash) {
+            return sym;
+        }
+        self.spans.reserve(1);
+        if self.needs_grow() {
+            let len = table_len_for(self.len() + 1);
+            self.rehash(Vec::with_capacity_in(len, self.allocator().clone()), len);
+        }
+        if !self.chunk_fits(s.len()) {
+            self.chunks.reserve(1);
+            let chunk = Vec::with_capacity_in(self.next_chunk_len(s.len()), self.allocator().clone());
+            self.chunks.push(chunk);
+        }
+        self.insert_new(s, hash)
+    }
+
+    /// Tries to return the symbol for `s`, interning it first if needed.
+    ///
+    /// Returns an error instead of aborting if an allocation fails, leaving the interner's contents unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` is longer than `u32::MAX` bytes or if `u32::MAX` strings have already been interned.
+    pub fn try_get_or_intern(&mut self, s: &str) -> Result<Symbol, TryReserveError> {
+        let hash = hash_str(s);
+        if let Ok(sym) = self.find(s, hash) {
+            return Ok(sym);
+        }
+        self.spans.try_reserve(1)?;
+        if self.needs_grow() {
+            let len = table_len_for(self.len() + 1);
+            self.rehash(Vec::try_with_capacity_in(len, self.allocator().clone())?, len);
+        }
+        if !self.chunk_fits(s.len()) {
+            self.chunks.try_reserve(1)?;
+            let chunk = Vec::try_with_capacity_in(self.next_chunk_len(s.len()), self.allocator().clone())?;
+            self.chunks.push(chunk);
+        }
+        Ok(self.insert_new(s, hash))
+    }
+
+    /// Returns the string for `sym`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sym` was not produced by this interner.
+    pub fn resolve(&self, sym: Symbol) -> &str {
+        self.try_resolve(sym).expect("symbol not found in this interner")
+    }
+
+    /// Returns the string for `sym`, or `None` if it is out of range for this interner.
+    pub fn try_resolve(&self, sym: Symbol) -> Option<&str> {
+        let span = self.spans.get(sym.0 as usize)?;
+        Some(span_str(&self.chunks, *span))
+    }
+
+    /// Returns an iterator over the symbols and strings, in interning order.
+    pub fn iter(&self) -> Iter<'_, A> {
+        Iter {
+            chunks: &self.chunks,
+            spans: self.spans.iter().enumerate(),
+        }
+    }
+
+    /// Probes for `s`, returning its symbol or the empty slot where it would go.
+    fn find(&self, s: &str, hash: u32) -> Result<Symbol, usize> {
+        if self.table.is_empty() {
+            return Err(0);
+        }
+        let mask = self.table.len() - 1;
+        let mut i = hash as usize & mask;
+        loop {
+            let slot = self.table[i];
+            if slot.sym == EMPTY {
+                return Err(i);
+            }
+            if slot.hash == hash && span_str(&self.chunks, self.spans[slot.sym as usize]) == s {
+                return Ok(Symbol(slot.sym));
+            }
+            i = (i + 1) & mask;
+        }
+    }
+
+    /// Keeps the load factor at or below 3/4, so probes always reach an empty slot.
+    fn needs_grow(&self) -> bool {
+        (self.len() + 1) * 4 > self.table.len() * 3
+    }
+
+    /// Moves every symbol into `table`, an empty vector with room for `len` slots, where `len` is a power of two.
+    fn rehash(&mut self, mut table: Vec<Slot, A>, len: usize) {
+        table.resize(len, EMPTY_SLOT);
+        let mask = table.len() - 1;
+        for slot in self.table.iter().filter(|slot| slot.sym != EMPTY) {
+            let mut i = slot.hash as usize & mask;
+            while table[i].sym != EMPTY {
+                i = (i + 1) & mask;
+            }
+            table[i] = *slot;
+        }
+        self.table = table;
+    }
+
+    fn chunk_fits(&self, len: usize) -> bool {
+        len == 0 ||
+            self.chunks
+                .last()
+                .is_some_and(|chunk| chunk.capacity() - chunk.len() >= len)
+    }
+
+    fn next_chunk_len(&self, len: usize) -> usize {
+        let grown = self
+            .chunks
+            .last()
+            .map_or(MIN_CHUNK, |chunk| (chunk.capacity() * 2).min(MAX_CHUNK));
+        grown.max(len)
+    }
+
+    /// Stores a string not yet in the table. Callers must have reserved a span, a table slot and chunk space.
+    fn insert_new(&mut self, s: &str, hash: u32) -> Symbol {
+        let len = u32::try_from(s.len()).expect("string too long to intern");
+        let sym = u32::try_from(self.len())
+            .ok()
+            .filter(|&sym| sym != EMPTY)
+            .expect("too many interned strings");
+
+        let span = match self.chunks.last_mut() {
+            Some(chunk) if len > 0 => {
+                let start = chunk.len() as u32;
+                // Fits in the reserved capacity, so this never reallocates the chunk
+                chunk.extend_from_slice(s.as_bytes());
+                Span {
+                    chunk: (self.chunks.len() - 1) as u32,
+                    start,
+                    len,
+                }
+            }
+            _ => Span {
+                chunk: 0,
+                start: 0,
+                len: 0,
+            },
+        };
+        self.spans.push(span);
+
+        let Err(i) = self.find(s, hash) else { unreachable!() };
+        self.table[i] = Slot { sym, hash };
+        Symbol(sym)
+    }
+}
+
+impl<A: Allocator + Clone + Default> Default for Interner<A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<A: Allocator + Clone> fmt::Debug for Interner<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, A: Allocator + Clone> IntoIterator for &'a Interner<A> {
+    type Item = (Symbol, &'a str);
+    type IntoIter = Iter<'a, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+fn span_str<A: Allocator>(chunks: &[Vec<u8, A>], span: Span) -> &str {
+    if span.len == 0 {
+        return "";
+    }
+    let start = span.start as usize;
+    let bytes = &chunks[span.chunk as usize][start..start + span.len as usize];
+    // SAFETY: spans only ever cover bytes copied from a `&str`
+    unsafe { str::from_utf8_unchecked(bytes) }
+}
+
+/// Table length for `symbols` entries at a load factor of at most 3/4.
+fn table_len_for(symbols: usize) -> usize {
+    (symbols * 4 / 3 + 1).next_power_of_two().max(8)
+}
+
+/// FxHash-style hash. The table uses the high half, which is better mixed than the low bits.
+fn hash_str(s: &str) -> u32 {
+    const K: u64 = 0x517c_c1b7_2722_0a95;
+    let mut hash = (s.len() as u64).wrapping_mul(K);
+    let (words, rest) = s.as_bytes().as_chunks::<8>();
+    for word in words {
+        hash = (hash.rotate_left(5) ^ u64::from_le_bytes(*word)).wrapping_mul(K);
+    }
+    for &byte in rest {
+        hash = (hash.rotate_left(5) ^ u64::from(byte)).wrapping_mul(K);
+    }
+    (hash >> 32) as u32
+}
+
+/// An iterator over the symbols and strings of an [`Interner`], in interning order.
+///
+/// This struct is created by [`Interner::iter`].
+pub struct Iter<'a, A: Allocator = Global> {
+    chunks: &'a [Vec<u8, A>],
+    spans: Enumerate<slice::Iter<'a, Span>>,
+}
+
+impl<'a, A: Allocator> Iter<'a, A> {
+    fn item(&self, (i, span): (usize, &Span)) -> (Symbol, &'a str) {
+        (Symbol(i as u32), span_str(self.chunks, *span))
+    }
+}
+
+impl<'a, A: Allocator> Iterator for Iter<'a, A> {
+    type Item = (Symbol, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.spans.next().map(|entry| self.item(entry))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
+
+impl<A: Allocator> DoubleEndedIterator for Iter<'_, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.spans.next_back().map(|entry| self.item(entry))
+    }
+}
+
+impl<A: Allocator> ExactSizeIterator for Iter<'_, A> {}
+
+impl<A: Allocator> FusedIterator for Iter<'_, A> {}
+
+impl<A: Allocator> Clone for Iter<'_, A> {
+    fn clone(&self) -> Self {
+        Iter {
+            chunks: self.chunks,
+            spans: self.spans.clone(),
+        }
+    }
+}
+
+impl<A: Allocator> fmt::Debug for Iter<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Symbol {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Symbol {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        u32::deserialize(deserializer).map(Symbol)
+    }
+}
+
+/// Serializes as the sequence of interned strings in symbol order, so deserializing restores the same symbols.
+#[cfg(feature = "serde")]
+impl<A: Allocator + Clone> serde::Serialize for Interner<A> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter().map(|(_, s)| s))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, A: Allocator + Clone + Default> serde::Deserialize<'de> for Interner<A> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        serde::de::DeserializeSeed::deserialize(crate::de::InternerSeed(A::default()), deserializer)
+    }
+}
@@ -18,8 +18,15 @@
 //! - UTF-8 correctness
 //! - Full `no_std` support via `extern crate alloc`
 //! - Custom allocator compatibility
+//! - Fallible allocation via `try_*` methods returning `TryReserveError`
 //! - Thread-safe operations
-//! - `format_in!` macro support
+//! - `format_in!` and `try_format_in!` macro support, including in `no_std` crates
+//! - Companion types: borrowed-or-owned `CowStr`, inline-first `SmallString` and never-allocating `ArrayString`
+//! - `StringLike` trait for writing code generic over the owned string types
+//! - `StrExt` and `StrSliceExt` for `replace_in`, `to_lowercase_in`, `join_in` and other allocating `str` methods
+//! - `Interner` for deduplicating strings into compact `Symbol` handles
+//! - Persistent `Rope` with `O(log n)` edits for large documents
+//! - Gap-buffer `GapString` for cursor-local editing
 //! - Serde serialization/deserialization (optional)
 //!
 //! ## Design Choices
@@ -31,24 +38,12 @@
 //!   to distinguish them from the default allocator versions.
 //!
 //! - **UTF-8 Safety**: All string operations maintain UTF-8 correctness, with proper handling of
-//!   character boundaries and byte lengths.
+//!   character boundaries and byte lengths. Byte vectors are validated when converted through
+//!   `from_utf8_in` or `TryFrom<Vec<u8, A>>`.
 //!
-//! - **Minimal Dependencies**: The implementation uses only stable features and core functionality,
-//!   avoiding unstable features to maintain compatibility and safety.
-//!
-//! ### Omitted Features
-//!
-//! Some features from the standard library's `String` implementation have been intentionally omitted:
-//!
-//! - `from_utf8_lossy`: Requires unstable features for efficient lossy UTF-8 conversion
-//! - `get`/`get_mut`: Can be worked around using string slicing and `split_at`
-//! - `drain`: Can be replaced with `split_off` and `retain` for most use cases
-//!
-//! These omissions are intentional to:
-//! - Keep the codebase small and maintainable
-//! - Avoid unstable features
-//! - Maintain safety guarantees
-//! - Provide workable alternatives through existing methods
+//! - **Minimal Dependencies**: The implementation depends only on `core` and `alloc`, but it does rely on
+//!   unstable library features and therefore on nightly: `allocator_api` throughout, `pattern` for the
+//!   `Pattern`-taking methods such as `remove_matches`, and `btreemap_alloc` when the `serde` feature is enabled.
 //!
 //! ## Usage
 //!
@@ -84,10 +79,31 @@
 
 #![no_std]
 #![feature(allocator_api)]
+#![feature(pattern)]
+#![cfg_attr(feature = "serde", feature(btreemap_alloc))]
 
 #[cfg(feature = "std")] extern crate std;
 
 extern crate alloc;
 
+pub mod array;
+pub mod clone_in;
+pub mod cow;
+#[cfg(feature = "serde")] pub mod de;
+pub mod gap;
+pub mod interner;
+pub mod rope;
+pub mod small;
+pub mod str_ext;
 pub mod string;
-pub use string::String;
+pub mod string_like;
+pub use array::{ArrayString, CapacityError};
+pub use clone_in::CloneIn;
+pub use cow::CowStr;
+pub use gap::GapString;
+pub use interner::{Interner, Symbol};
+pub use rope::Rope;
+pub use small::SmallString;
+pub use str_ext::{StrExt, StrSliceExt};
+pub use string::{Drain, FromUtf16Error, FromUtf8Error, IntoChars, String};
+pub use string_like::StringLike;
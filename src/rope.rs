@@ -0,0 +1,767 @@
+//! A persistent rope for large, frequently edited text.
+//!
+//! [`Rope`] stores text in a balanced binary tree of atomically reference-counted nodes, each allocated in the rope's
+//! allocator. Edits copy only the path from the root to the affected leaves, so inserting, removing or slicing is
+//! `O(log n)` and cloning a rope is `O(1)`, with clones sharing every untouched node.
+//!
+//! ```
+//! #![feature(allocator_api)]
+//!
+//! use std::alloc::Global;
+//! use string_alloc::Rope;
+//!
+//! let mut rope = Rope::from_str_in("Hello, world!", Global);
+//! let before = rope.clone();
+//! rope.insert(7, "wide ");
+//! rope.remove(..5);
+//! assert_eq!(rope, ", wide world!");
+//! assert_eq!(before, "Hello, world!");
+//! assert_eq!(rope.slice(2..6), "wide");
+//! ```
+
+use alloc::alloc::Global;
+use alloc::sync::Arc;
+use core::alloc::Allocator;
+use core::iter::{FlatMap, FusedIterator};
+use core::ops::{Bound, RangeBounds};
+use core::{fmt, str};
+
+use crate::String;
+
+/// Leaves hold at most this many bytes. Smaller leaves make edits cheaper and iteration slower.
+const MAX_LEAF: usize = 1024;
+
+type Link<A> = Arc<Node<A>, A>;
+
+struct Node<A: Allocator + Clone> {
+    len: usize,
+    chars: usize,
+    /// Zero for leaves.
+    height: u8,
+    kind: Kind<A>,
+}
+
+enum Kind<A: Allocator + Clone> {
+    /// Never empty.
+    Leaf(String<A>),
+    Branch(Link<A>, Link<A>),
+}
+
+impl<A: Allocator + Clone> Node<A> {
+    fn children(&self) -> (&Link<A>, &Link<A>) {
+        match &self.kind {
+            Kind::Branch(left, right) => (left, right),
+            Kind::Leaf(_) => unreachable!("leaves have no children"),
+        }
+    }
+
+    /// Returns the leaf containing byte `idx` and the byte offset at which that leaf starts.
+    fn leaf_at(&self, mut idx: usize) -> (&str, usize) {
+        let mut node = self;
+        let mut offset = 0;
+        loop {
+            match &node.kind {
+                Kind::Leaf(text) => return (text, offset),
+                Kind::Branch(left, _) if idx < left.len => node = left,
+                Kind::Branch(left, right) => {
+                    idx -= left.len;
+                    offset += left.len;
+                    node = right;
+                }
+            }
+        }
+    }
+
+    fn char_to_byte(&self, mut char_idx: usize) -> usize {
+        let mut node = self;
+        let mut offset = 0;
+        loop {
+            match &node.kind {
+                Kind::Leaf(text) => {
+                    return offset + text.char_indices().nth(char_idx).map_or(text.len(), |(i, _)| i);
+                }
+                Kind::Branch(left, _) if char_idx < left.chars => node = left,
+                Kind::Branch(left, right) => {
+                    char_idx -= left.chars;
+                    offset += left.len;
+                    node = right;
+                }
+            }
+        }
+    }
+
+    fn byte_to_char(&self, mut byte_idx: usize) -> usize {
+        let mut node = self;
+        let mut chars = 0;
+        loop {
+            match &node.kind {
+                Kind::Leaf(text) => {
+                    assert!(text.is_char_boundary(byte_idx), "byte index is not a char boundary");
+                    return chars + text[..byte_idx].chars().count();
+                }
+                Kind::Branch(left, _) if byte_idx < left.len => node = left,
+                Kind::Branch(left, right) => {
+                    byte_idx -= left.len;
+                    chars += left.chars;
+                    node = right;
+                }
+            }
+        }
+    }
+}
+
+/// A persistent, balanced rope of UTF-8 text, allocated in `A`.
+///
+/// Byte offsets must lie on char boundaries, as with [`String`]. The `*_chars` variants take offsets counted in
+/// chars instead, converting them in `O(log n)`.
+///
+/// A `Rope<A>` is `Send` and `Sync` whenever `A` is, so a cheap clone can be handed to another thread as a snapshot
+/// while editing continues.
+pub struct Rope<A: Allocator + Clone = Global> {
+    root: Option<Link<A>>,
+    alloc: A,
+}
+
+impl<A: Allocator + Clone> Rope<A> {
+    /// Creates a new empty rope. It does not allocate until text is added.
+    pub fn new_in(alloc: A) -> Self {
+        Rope { root: None, alloc }
+    }
+
+    /// Creates a new rope from a string slice.
+    pub fn from_str_in(s: &str, alloc: A) -> Self {
+        let root = build(s, &alloc);
+        Rope { root, alloc }
+    }
+
+    /// Returns a reference to the underlying allocator.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns the length of this rope, in bytes.
+    pub fn len(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.len)
+    }
+
+    /// Returns the length of this rope, in chars.
+    pub fn len_chars(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.chars)
+    }
+
+    /// Returns `true` if this rope has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns `true` if the byte at `idx` is the first byte of a char, or `idx` is the length.
+    pub fn is_char_boundary(&self, idx: usize) -> bool {
+        match &self.root {
+            _ if idx == self.len() => true,
+            Some(root) if idx < root.len => {
+                let (leaf, offset) = root.leaf_at(idx);
+                leaf.is_char_boundary(idx - offset)
+            }
+            _ => false,
+        }
+    }
+
+    /// Converts a char offset into a byte offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is larger than the number of chars in the rope.
+    pub fn char_to_byte(&self, char_idx: usize) -> usize {
+        assert!(
+            char_idx <= self.len_chars(),
+            "char index (is {}) should be <= len_chars (is {})",
+            char_idx,
+            self.len_chars()
+        );
+        match &self.root {
+            Some(root) => root.char_to_byte(char_idx),
+            None => 0,
+        }
+    }
+
+    /// Converts a byte offset into a char offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_idx` is larger than the length or does not lie on a char boundary.
+    pub fn byte_to_char(&self, byte_idx: usize) -> usize {
+        assert!(
+            byte_idx <= self.len(),
+            "byte index (is {}) should be <= len (is {})",
+            byte_idx,
+            self.len()
+        );
+        match &self.root {
+            Some(root) if byte_idx < root.len => root.byte_to_char(byte_idx),
+            _ => self.len_chars(),
+        }
+    }
+
+    /// Inserts a string slice into this rope at a byte position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the length or does not lie on a char boundary.
+    pub fn insert(&mut self, idx: usize, s: &str) {
+        assert!(self.is_char_boundary(idx), "insertion index is not a char boundary");
+        if s.is_empty() {
+            return;
+        }
+        let Some(root) = self.root.take() else {
+            self.root = build(s, &self.alloc);
+            return;
+        };
+        // Small edits copy a single leaf; anything larger is spliced in between the split halves
+        self.root = Some(match insert_small(&root, idx, s, &self.alloc) {
+            Some(root) => root,
+            None => {
+                let (left, right) = split(&root, idx, &self.alloc);
+                let middle = build(s, &self.alloc);
+                let left = join_opt(left, middle, &self.alloc);
+                join_opt(left, right, &self.alloc).expect("inserted text is not empty")
+            }
+        });
+    }
+
+    /// Inserts a string slice into this rope at a char position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is larger than the number of chars in the rope.
+    pub fn insert_at_char(&mut self, char_idx: usize, s: &str) {
+        let idx = self.char_to_byte(char_idx);
+        self.insert(idx, s);
+    }
+
+    /// Appends a string slice onto the end of this rope.
+    pub fn push_str(&mut self, s: &str) {
+        self.insert(self.len(), s);
+    }
+
+    /// Appends another rope onto the end of this one, sharing its nodes.
+    pub fn append(&mut self, other: &Rope<A>) {
+        self.root = join_opt(self.root.take(), other.root.clone(), &self.alloc);
+    }
+
+    /// Removes a byte range from this rope.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point or end point do not lie on a char boundary, or if they're out of bounds.
+    pub fn remove<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.byte_range(range);
+        let Some(root) = self.root.take() else { return };
+        let (left, rest) = split(&root, start, &self.alloc);
+        let right = rest.and_then(|rest| split(&rest, end - start, &self.alloc).1);
+        self.root = join_opt(left, right, &self.alloc);
+    }
+
+    /// Removes a char range from this rope.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn remove_chars<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.char_range(range);
+        self.remove(start..end);
+    }
+
+    /// Returns a new rope holding a byte range of this one, sharing its nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point or end point do not lie on a char boundary, or if they're out of bounds.
+    pub fn slice<R>(&self, range: R) -> Rope<A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.byte_range(range);
+        let root = self.root.as_ref().and_then(|root| {
+            let (_, rest) = split(root, start, &self.alloc);
+            rest.and_then(|rest| split(&rest, end - start, &self.alloc).0)
+        });
+        Rope {
+            root,
+            alloc: self.alloc.clone(),
+        }
+    }
+
+    /// Returns a new rope holding a char range of this one, sharing its nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn slice_chars<R>(&self, range: R) -> Rope<A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.char_range(range);
+        self.slice(start..end)
+    }
+
+    /// Splits the rope into two at the given byte index, returning everything after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is larger than the length or does not lie on a char boundary.
+    pub fn split_off(&mut self, at: usize) -> Rope<A> {
+        assert!(self.is_char_boundary(at), "split index is not a char boundary");
+        let (left, right) = match self.root.take() {
+            Some(root) => split(&root, at, &self.alloc),
+            None => (None, None),
+        };
+        self.root = left;
+        Rope {
+            root: right,
+            alloc: self.alloc.clone(),
+        }
+    }
+
+    /// Truncates this rope, removing all contents.
+    pub fn clear(&mut self) {
+        self.root = None;
+    }
+
+    /// Returns an iterator over the rope's text in contiguous chunks, front to back.
+    pub fn chunks(&self) -> Chunks<'_, A> {
+        Chunks {
+            root: self.root.as_deref(),
+            start: 0,
+            end: self.len(),
+        }
+    }
+
+    /// Returns an iterator over the chars of this rope.
+    pub fn chars(&self) -> Chars<'_, A> {
+        Chars {
+            inner: self.chunks().flat_map(str::chars as fn(&str) -> str::Chars<'_>),
+        }
+    }
+
+    fn byte_range<R: RangeBounds<usize>>(&self, range: R) -> (usize, usize) {
+        let (start, end) = bounds(range, self.len());
+        assert!(self.is_char_boundary(start), "range start is not a char boundary");
+        assert!(self.is_char_boundary(end), "range end is not a char boundary");
+        (start, end)
+    }
+
+    fn char_range<R: RangeBounds<usize>>(&self, range: R) -> (usize, usize) {
+        let (start, end) = bounds(range, self.len_chars());
+        (self.char_to_byte(start), self.char_to_byte(end))
+    }
+}
+
+/// Resolves a range against a length, panicking if it is decreasing or out of bounds.
+fn bounds<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.checked_add(1).expect("range start overflows"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&n) => n.checked_add(1).expect("range end overflows"),
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end,
+        "range start (is {}) should be <= range end (is {})",
+        start,
+        end
+    );
+    assert!(end <= len, "range end (is {}) should be <= len (is {})", end, len);
+    (start, end)
+}
+
+fn leaf<A: Allocator + Clone>(text: String<A>, alloc: &A) -> Link<A> {
+    let node = Node {
+        len: text.len(),
+        chars: text.chars().count(),
+        height: 0,
+        kind: Kind::Leaf(text),
+    };
+    Arc::new_in(node, alloc.clone())
+}
+
+fn leaf_from<A: Allocator + Clone>(text: &str, alloc: &A) -> Link<A> {
+    leaf(String::from_str_in(text, alloc.clone()), alloc)
+}
+
+fn branch<A: Allocator + Clone>(left: Link<A>, right: Link<A>, alloc: &A) -> Link<A> {
+    let node = Node {
+        len: left.len + right.len,
+        chars: left.chars + right.chars,
+        height: left.height.max(right.height) + 1,
+        kind: Kind::Branch(left, right),
+    };
+    Arc::new_in(node, alloc.clone())
+}
+
+/// Builds a perfectly balanced tree of full leaves from `s`.
+fn build<A: Allocator + Clone>(s: &str, alloc: &A) -> Option<Link<A>> {
+    if s.is_empty() {
+        return None;
+    }
+    if s.len() <= MAX_LEAF {
+        return Some(leaf_from(s, alloc));
+    }
+    // Split on a leaf multiple so every leaf except the last is full
+    let leaves = s.len().div_ceil(MAX_LEAF);
+    let mid = s.floor_char_boundary(leaves / 2 * MAX_LEAF);
+    let (left, right) = s.split_at(mid);
+    Some(branch(build(left, alloc)?, build(right, alloc)?, alloc))
+}
+
+/// Joins two subtrees whose heights differ by at most two, rotating once if they differ by exactly two.
+fn balance<A: Allocator + Clone>(left: Link<A>, right: Link<A>, alloc: &A) -> Link<A> {
+    if left.height > right.height + 1 {
+        let (ll, lr) = left.children();
+        if ll.height >= lr.height {
+            branch(ll.clone(), branch(lr.clone(), right, alloc), alloc)
+        } else {
+            let (lrl, lrr) = lr.children();
+            branch(
+                branch(ll.clone(), lrl.clone(), alloc),
+                branch(lrr.clone(), right, alloc),
+                alloc,
+            )
+        }
+    } else if right.height > left.height + 1 {
+        let (rl, rr) = right.children();
+        if rr.height >= rl.height {
+            branch(branch(left, rl.clone(), alloc), rr.clone(), alloc)
+        } else {
+            let (rll, rlr) = rl.children();
+            branch(
+                branch(left, rll.clone(), alloc),
+                branch(rlr.clone(), rr.clone(), alloc),
+                alloc,
+            )
+        }
+    } else {
+        branch(left, right, alloc)
+    }
+}
+
+/// Concatenates two trees, descending the taller one's spine so the result stays balanced.
+fn join<A: Allocator + Clone>(left: Link<A>, right: Link<A>, alloc: &A) -> Link<A> {
+    if left.height > right.height + 1 {
+        let (ll, lr) = left.children();
+        let joined = join(lr.clone(), right, alloc);
+        balance(ll.clone(), joined, alloc)
+    } else if right.height > left.height + 1 {
+        let (rl, rr) = right.children();
+        let joined = join(left, rl.clone(), alloc);
+        balance(joined, rr.clone(), alloc)
+    } else {
+        match (&left.kind, &right.kind) {
+            // Merge small neighbours so repeated edits don't fragment the text into tiny leaves
+            (Kind::Leaf(l), Kind::Leaf(r)) if l.len() + r.len() <= MAX_LEAF => {
+                let mut text = String::with_capacity_in(l.len() + r.len(), alloc.clone());
+                text.push_str(l);
+                text.push_str(r);
+                leaf(text, alloc)
+            }
+            _ => branch(left, right, alloc),
+        }
+    }
+}
+
+fn join_opt<A: Allocator + Clone>(left: Option<Link<A>>, right: Option<Link<A>>, alloc: &A) -> Option<Link<A>> {
+    match (left, right) {
+        (Some(left), Some(right)) => Some(join(left, right, alloc)),
+        (left, right) => left.or(right),
+    }
+}
+
+/// Splits a tree at byte `idx`, which must lie on a char boundary.
+fn split<A: Allocator + Clone>(node: &Link<A>, idx: usize, alloc: &A) -> (Option<Link<A>>, Option<Link<A>>) {
+    if idx == 0 {
+        return (None, Some(node.clone()));
+    }
+    if idx >= node.len {
+        return (Some(node.clone()), None);
+    }
+    match &node.kind {
+        Kind::Leaf(text) => (
+            Some(leaf_from(&text[..idx], alloc)),
+            Some(leaf_from(&text[idx..], alloc)),
+        ),
+        Kind::Branch(left, right) if idx <= left.len => {
+            let (a, b) = split(left, idx, alloc);
+            (a, join_opt(b, Some(right.clone()), alloc))
+        }
+        Kind::Branch(left, right) => {
+            let (a, b) = split(right, idx - left.len, alloc);
+            (join_opt(Some(left.clone()), a, alloc), b)
+        }
+    }
+}
+
+/// Copies the path to the leaf containing `idx`, inserting `s` into it, or returns `None` if the leaf would overflow.
+fn insert_small<A: Allocator + Clone>(node: &Link<A>, idx: usize, s: &str, alloc: &A) -> Option<Link<A>> {
+    match &node.kind {
+        Kind::Leaf(text) => {
+            if text.len() + s.len() > MAX_LEAF {
+                return None;
+            }
+            let mut new = String::with_capacity_in(text.len() + s.len(), alloc.clone());
+            new.push_str(&text[..idx]);
+            new.push_str(s);
+            new.push_str(&text[idx..]);
+            Some(leaf(new, alloc))
+        }
+        Kind::Branch(left, right) if idx <= left.len => {
+            Some(branch(insert_small(left, idx, s, alloc)?, right.clone(), alloc))
+        }
+        Kind::Branch(left, right) => Some(branch(
+            left.clone(),
+            insert_small(right, idx - left.len, s, alloc)?,
+            alloc,
+        )),
+    }
+}
+
+impl<A: Allocator + Clone> Clone for Rope<A> {
+    /// Clones the rope in `O(1)`, sharing every node with the original.
+    fn clone(&self) -> Self {
+        Rope {
+            root: self.root.clone(),
+            alloc: self.alloc.clone(),
+        }
+    }
+}
+
+impl<A: Allocator + Clone + Default> Default for Rope<A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<A: Allocator + Clone> fmt::Display for Rope<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        pad_chunks(f, self.chunks())
+    }
+}
+
+impl<A: Allocator + Clone> fmt::Debug for Rope<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_chunks(f, self.chunks())
+    }
+}
+
+impl<A: Allocator + Clone> fmt::Write for Rope<A> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl<A: Allocator + Clone, B: Allocator + Clone> PartialEq<Rope<B>> for Rope<A> {
+    fn eq(&self, other: &Rope<B>) -> bool {
+        self.len() == other.len() &&
+            self.chunks()
+                .flat_map(str::bytes)
+                .eq(other.chunks().flat_map(str::bytes))
+    }
+}
+
+impl<A: Allocator + Clone> Eq for Rope<A> {}
+
+impl<A: Allocator + Clone> PartialEq<str> for Rope<A> {
+    fn eq(&self, other: &str) -> bool {
+        self.len() == other.len() && self.chunks().flat_map(str::bytes).eq(other.bytes())
+    }
+}
+
+impl<A: Allocator + Clone> PartialEq<&str> for Rope<A> {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl<A: Allocator + Clone, B: Allocator> PartialEq<String<B>> for Rope<A> {
+    fn eq(&self, other: &String<B>) -> bool {
+        *self == **other
+    }
+}
+
+impl<A: Allocator + Clone> From<String<A>> for Rope<A> {
+    fn from(s: String<A>) -> Self {
+        Rope::from_str_in(&s, s.allocator().clone())
+    }
+}
+
+impl<A: Allocator + Clone> From<&Rope<A>> for String<A> {
+    fn from(rope: &Rope<A>) -> Self {
+        let mut s = String::with_capacity_in(rope.len(), rope.alloc.clone());
+        rope.chunks().for_each(|chunk| s.push_str(chunk));
+        s
+    }
+}
+
+impl<A: Allocator + Clone> From<Rope<A>> for String<A> {
+    fn from(rope: Rope<A>) -> Self {
+        String::from(&rope)
+    }
+}
+
+/// Writes `chunks` as a single string, applying the width, fill, alignment and precision flags the way
+/// [`fmt::Formatter::pad`] does: truncate to `precision` chars, then pad to `width` chars.
+pub(crate) fn pad_chunks<'a, I>(f: &mut fmt::Formatter<'_>, chunks: I) -> fmt::Result
+where
+    I: Iterator<Item = &'a str> + Clone,
+{
+    use fmt::Write;
+
+    if f.width().is_none() && f.precision().is_none() {
+        return chunks.into_iter().try_for_each(|chunk| f.write_str(chunk));
+    }
+    let len = chunks
+        .clone()
+        .flat_map(str::chars)
+        .take(f.precision().unwrap_or(usize::MAX))
+        .count();
+    let padding = f.width().map_or(0, |width| width.saturating_sub(len));
+    let (pre, post) = match f.align() {
+        Some(fmt::Alignment::Right) => (padding, 0),
+        Some(fmt::Alignment::Center) => (padding / 2, padding.div_ceil(2)),
+        _ => (0, padding),
+    };
+    let fill = f.fill();
+    (0..pre).try_for_each(|_| f.write_char(fill))?;
+    let mut left = len;
+    for chunk in chunks {
+        if left == 0 {
+            break;
+        }
+        match chunk.char_indices().nth(left) {
+            Some((end, _)) => {
+                f.write_str(&chunk[..end])?;
+                left = 0;
+            }
+            None => {
+                f.write_str(chunk)?;
+                left -= chunk.chars().count();
+            }
+        }
+    }
+    (0..post).try_for_each(|_| f.write_char(fill))
+}
+
+/// Writes `chunks` as a single quoted string, escaped the way `str`'s `Debug` does it.
+pub(crate) fn debug_chunks<'a>(f: &mut fmt::Formatter<'_>, chunks: impl Iterator<Item = &'a str>) -> fmt::Result {
+    f.write_str("\"")?;
+    for ch in chunks.flat_map(str::chars) {
+        match ch {
+            '\'' => f.write_str("'")?,
+            _ => fmt::Display::fmt(&ch.escape_debug(), f)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// An iterator over the contiguous chunks of a [`Rope`].
+///
+/// This struct is created by [`Rope::chunks`]. Every chunk is non-empty.
+pub struct Chunks<'a, A: Allocator + Clone = Global> {
+    root: Option<&'a Node<A>>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, A: Allocator + Clone> Iterator for Chunks<'a, A> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.start >= self.end {
+            return None;
+        }
+        let (leaf, offset) = self.root?.leaf_at(self.start);
+        let chunk = &leaf[self.start - offset..leaf.len().min(self.end - offset)];
+        self.start += chunk.len();
+        Some(chunk)
+    }
+}
+
+impl<'a, A: Allocator + Clone> DoubleEndedIterator for Chunks<'a, A> {
+    fn next_back(&mut self) -> Option<&'a str> {
+        if self.start >= self.end {
+            return None;
+        }
+        let (leaf, offset) = self.root?.leaf_at(self.end - 1);
+        let chunk = &leaf[self.start.saturating_sub(offset)..self.end - offset];
+        self.end -= chunk.len();
+        Some(chunk)
+    }
+}
+
+impl<A: Allocator + Clone> FusedIterator for Chunks<'_, A> {}
+
+impl<A: Allocator + Clone> Clone for Chunks<'_, A> {
+    fn clone(&self) -> Self {
+        Chunks {
+            root: self.root,
+            start: self.start,
+            end: self.end,
+        }
+    }
+}
+
+impl<A: Allocator + Clone> fmt::Debug for Chunks<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the chars of a [`Rope`].
+///
+/// This struct is created by [`Rope::chars`].
+pub struct Chars<'a, A: Allocator + Clone = Global> {
+    inner: FlatMap<Chunks<'a, A>, str::Chars<'a>, fn(&'a str) -> str::Chars<'a>>,
+}
+
+impl<A: Allocator + Clone> Iterator for Chars<'_, A> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<A: Allocator + Clone> DoubleEndedIterator for Chars<'_, A> {
+    fn next_back(&mut self) -> Option<char> {
+        self.inner.next_back()
+    }
+}
+
+impl<A: Allocator + Clone> FusedIterator for Chars<'_, A> {}
+
+impl<A: Allocator + Clone> Clone for Chars<'_, A> {
+    fn clone(&self) -> Self {
+        Chars {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<A: Allocator + Clone> fmt::Debug for Chars<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
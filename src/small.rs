@@ -0,0 +1,553 @@
+//! A small-string-optimized string that keeps short contents inline and spills into an allocator.
+
+use alloc::collections::TryReserveError;
+use core::alloc::Allocator;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+use core::str;
+
+use ::alloc::alloc::Global;
+
+use crate::array::retain_inline;
+use crate::String;
+
+/// A string that stores up to `N` bytes inline and moves into allocator `A` once it grows past that.
+///
+/// Short strings never touch the allocator. Once spilled, the contents live in a regular [`String<A>`] and stay
+/// there until [`SmallString::shrink_to_fit`] brings them back inline. Converting a spilled `SmallString` into a
+/// `String<A>`, or a `String<A>` into a `SmallString`, moves the buffer without copying.
+///
+/// The inline buffer shares its storage with the spilled string, so a `SmallString<N>` takes one word more than the
+/// larger of `N` bytes and a `String`: on 64-bit targets, `SmallString<24>` is 32 bytes against 24 for `String`.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use std::alloc::Global;
+/// use string_alloc::SmallString;
+///
+/// let mut s = SmallString::<8>::from_str_in("ident", Global);
+/// assert!(!s.spilled());
+/// s.push_str("_with_a_long_suffix");
+/// assert!(s.spilled());
+/// assert_eq!(&*s, "ident_with_a_long_suffix");
+/// ```
+pub struct SmallString<const N: usize, A: Allocator = Global> {
+    /// Length of the inline contents, or `SPILLED` once they have moved into the allocator.
+    len: usize,
+    data: Data<N, A>,
+}
+
+/// Marks a `SmallString` whose contents live in `Data::heap`.
+const SPILLED: usize = usize::MAX;
+
+/// The inline buffer and the spilled string share their storage, since only one of them is live at a time.
+union Data<const N: usize, A: Allocator> {
+    inline: ManuallyDrop<Inline<N, A>>,
+    heap: ManuallyDrop<String<A>>,
+}
+
+struct Inline<const N: usize, A> {
+    buf: [u8; N],
+    /// Held here until the first spill moves it into the string.
+    alloc: A,
+}
+
+impl<const N: usize, A: Allocator> SmallString<N, A> {
+    /// Creates a new empty `SmallString` that will spill into the specified allocator.
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            len: 0,
+            data: Data {
+                inline: ManuallyDrop::new(Inline { buf: [0; N], alloc }),
+            },
+        }
+    }
+
+    /// Creates a new empty `SmallString` with at least the specified capacity, spilling right away if it exceeds
+    /// `N`.
+    pub fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        if cap <= N {
+            Self::new_in(alloc)
+        } else {
+            Self::from_heap(String::with_capacity_in(cap, alloc))
+        }
+    }
+
+    fn from_heap(heap: String<A>) -> Self {
+        Self {
+            len: SPILLED,
+            data: Data {
+                heap: ManuallyDrop::new(heap),
+            },
+        }
+    }
+
+    /// Creates a new `SmallString` from a string slice, storing it inline if it fits.
+    pub fn from_str_in(s: &str, alloc: A) -> Self {
+        let mut res = Self::with_capacity_in(s.len(), alloc);
+        res.push_str(s);
+        res
+    }
+
+    /// Tries to create a new `SmallString` from a string slice.
+    ///
+    /// Returns an error instead of aborting if the string does not fit inline and the allocation fails.
+    pub fn try_from_str_in(s: &str, alloc: A) -> Result<Self, TryReserveError> {
+        let mut res = Self::new_in(alloc);
+        res.try_push_str(s)?;
+        Ok(res)
+    }
+
+    /// Returns `true` if the contents have moved into the allocator.
+    pub fn spilled(&self) -> bool {
+        self.len == SPILLED
+    }
+
+    fn heap(&self) -> Option<&String<A>> {
+        if self.spilled() {
+            Some(unsafe { &self.data.heap })
+        } else {
+            None
+        }
+    }
+
+    fn heap_mut(&mut self) -> Option<&mut String<A>> {
+        if self.spilled() {
+            Some(unsafe { &mut self.data.heap })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the inline buffer. Must only be called while the contents are inline.
+    unsafe fn inline_mut(&mut self) -> &mut ManuallyDrop<Inline<N, A>> {
+        debug_assert!(!self.spilled());
+        unsafe { &mut self.data.inline }
+    }
+
+    /// Returns a reference to the allocator used once the string spills.
+    pub fn allocator(&self) -> &A {
+        match self.heap() {
+            Some(heap) => heap.allocator(),
+            None => unsafe { &self.data.inline.alloc },
+        }
+    }
+
+    /// Returns the length of this `SmallString`, in bytes.
+    pub fn len(&self) -> usize {
+        match self.heap() {
+            Some(heap) => heap.len(),
+            None => self.len,
+        }
+    }
+
+    /// Returns `true` if this `SmallString` has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the capacity of this `SmallString`, in bytes. This is `N` until it spills.
+    pub fn capacity(&self) -> usize {
+        match self.heap() {
+            Some(heap) => heap.capacity(),
+            None => N,
+        }
+    }
+
+    /// Returns a byte slice of this `SmallString`'s contents.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self.heap() {
+            Some(heap) => heap.as_bytes(),
+            None => unsafe { &self.data.inline.buf[..self.len] },
+        }
+    }
+
+    /// Extracts a string slice containing the entire `SmallString`.
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Converts this `SmallString` into a mutable string slice.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        if self.spilled() {
+            return unsafe { (*self.data.heap).as_mut_str() };
+        }
+        let len = self.len;
+        unsafe { str::from_utf8_unchecked_mut(&mut self.inline_mut().buf[..len]) }
+    }
+
+    /// Moves the inline contents into the allocator, with room for `additional` more bytes.
+    fn spill(&mut self, additional: usize) {
+        let len = self.len;
+        let Inline { buf, alloc } = unsafe { ManuallyDrop::take(self.inline_mut()) };
+        // Switch over before reserving, so a capacity overflow panic leaves a valid (if emptied) string behind
+        self.data.heap = ManuallyDrop::new(String::new_in(alloc));
+        self.len = SPILLED;
+        let heap = unsafe { &mut self.data.heap };
+        heap.reserve(len.saturating_add(additional));
+        heap.push_str(unsafe { str::from_utf8_unchecked(&buf[..len]) });
+    }
+
+    fn try_spill(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let len = self.len;
+        let Inline { buf, alloc } = unsafe { ManuallyDrop::take(self.inline_mut()) };
+        let mut heap = String::new_in(alloc);
+        if let Err(e) = heap.try_reserve(len.saturating_add(additional)) {
+            let (_, _, _, alloc) = heap.into_raw_parts_with_alloc();
+            self.data.inline = ManuallyDrop::new(Inline { buf, alloc });
+            return Err(e);
+        }
+        heap.push_str(unsafe { str::from_utf8_unchecked(&buf[..len]) });
+        self.data.heap = ManuallyDrop::new(heap);
+        self.len = SPILLED;
+        Ok(())
+    }
+
+    /// Ensures that the capacity is at least `additional` bytes larger than the length, spilling if needed.
+    pub fn reserve(&mut self, additional: usize) {
+        let len = self.len;
+        match self.heap_mut() {
+            Some(heap) => heap.reserve(additional),
+            None if len.saturating_add(additional) > N => self.spill(additional),
+            None => {}
+        }
+    }
+
+    /// Tries to reserve capacity for at least `additional` more bytes, spilling if needed.
+    ///
+    /// Returns an error instead of aborting if the allocation fails.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let len = self.len;
+        match self.heap_mut() {
+            Some(heap) => heap.try_reserve(additional),
+            None if len.saturating_add(additional) > N => self.try_spill(additional),
+            None => Ok(()),
+        }
+    }
+
+    /// Moves spilled contents back inline if they fit, or shrinks the allocation to match the length otherwise.
+    pub fn shrink_to_fit(&mut self) {
+        let Some(heap) = self.heap_mut() else {
+            return;
+        };
+        let len = heap.len();
+        if len > N {
+            heap.shrink_to_fit();
+            return;
+        }
+        let mut buf = [0; N];
+        buf[..len].copy_from_slice(heap.as_bytes());
+        let mut heap = unsafe { ManuallyDrop::take(&mut self.data.heap) };
+        heap.clear();
+        // Shrinking an empty `Vec` releases its buffer, leaving only the allocator to take back
+        heap.shrink_to_fit();
+        let (_, _, _, alloc) = heap.into_raw_parts_with_alloc();
+        self.data.inline = ManuallyDrop::new(Inline { buf, alloc });
+        self.len = len;
+    }
+
+    /// Appends a given string slice onto the end of this `SmallString`.
+    pub fn push_str(&mut self, s: &str) {
+        if !self.spilled() && self.len + s.len() <= N {
+            let len = self.len;
+            unsafe { self.inline_mut() }.buf[len..len + s.len()].copy_from_slice(s.as_bytes());
+            self.len += s.len();
+            return;
+        }
+        self.reserve(s.len());
+        self.heap_mut().expect("reserve spills past N").push_str(s);
+    }
+
+    /// Tries to append a given string slice onto the end of this `SmallString`.
+    ///
+    /// Returns an error instead of aborting if the allocation fails, leaving the string unchanged.
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), TryReserveError> {
+        self.try_reserve(s.len())?;
+        self.push_str(s);
+        Ok(())
+    }
+
+    /// Appends the given char to the end of this `SmallString`.
+    pub fn push(&mut self, ch: char) {
+        let mut buf = [0; 4];
+        self.push_str(ch.encode_utf8(&mut buf));
+    }
+
+    /// Tries to append the given char to the end of this `SmallString`.
+    ///
+    /// Returns an error instead of aborting if the allocation fails, leaving the string unchanged.
+    pub fn try_push(&mut self, ch: char) -> Result<(), TryReserveError> {
+        let mut buf = [0; 4];
+        self.try_push_str(ch.encode_utf8(&mut buf))
+    }
+
+    /// Removes the last character and returns it.
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.truncate(self.len() - ch.len_utf8());
+        Some(ch)
+    }
+
+    /// Inserts a character at a byte position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the length, or if it does not lie on a char boundary.
+    pub fn insert(&mut self, idx: usize, ch: char) {
+        assert!(self.is_char_boundary(idx));
+        let ch_len = ch.len_utf8();
+        if self.spilled() || self.len + ch_len > N {
+            self.reserve(ch_len);
+            self.heap_mut().expect("reserve spills past N").insert(idx, ch);
+            return;
+        }
+        let len = self.len;
+        let buf = &mut unsafe { self.inline_mut() }.buf;
+        buf.copy_within(idx..len, idx + ch_len);
+        ch.encode_utf8(&mut buf[idx..idx + ch_len]);
+        self.len += ch_len;
+    }
+
+    /// Removes a char at a byte position and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than or equal to the length, or if it does not lie on a char boundary.
+    pub fn remove(&mut self, idx: usize) -> char {
+        if let Some(heap) = self.heap_mut() {
+            return heap.remove(idx);
+        }
+        let ch = match self[idx..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string"),
+        };
+        let len = self.len;
+        unsafe { self.inline_mut() }
+            .buf
+            .copy_within(idx + ch.len_utf8()..len, idx);
+        self.len -= ch.len_utf8();
+        ch
+    }
+
+    /// Shortens this `SmallString` to the specified length in bytes. This never moves spilled contents back inline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a char boundary.
+    pub fn truncate(&mut self, new_len: usize) {
+        if let Some(heap) = self.heap_mut() {
+            heap.truncate(new_len);
+        } else if new_len <= self.len {
+            assert!(self.is_char_boundary(new_len));
+            self.len = new_len;
+        }
+    }
+
+    /// Truncates this `SmallString`, removing all contents but keeping its capacity.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Retains only the characters specified by the predicate.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(char) -> bool,
+    {
+        if let Some(heap) = self.heap_mut() {
+            heap.retain(f);
+            return;
+        }
+        retain_inline(unsafe { &mut (*self.data.inline).buf }, &mut self.len, f);
+    }
+
+    /// Converts this `SmallString` into a `String<A>`. Spilled contents are moved without copying.
+    pub fn into_string(self) -> String<A> {
+        let mut this = ManuallyDrop::new(self);
+        if this.spilled() {
+            return unsafe { ManuallyDrop::take(&mut this.data.heap) };
+        }
+        let len = this.len;
+        let Inline { buf, alloc } = unsafe { ManuallyDrop::take(this.inline_mut()) };
+        String::from_str_in(unsafe { str::from_utf8_unchecked(&buf[..len]) }, alloc)
+    }
+}
+
+impl<const N: usize, A: Allocator> Drop for SmallString<N, A> {
+    fn drop(&mut self) {
+        if self.spilled() {
+            unsafe { ManuallyDrop::drop(&mut self.data.heap) }
+        } else {
+            unsafe { ManuallyDrop::drop(&mut self.data.inline) }
+        }
+    }
+}
+
+impl<const N: usize, A: Allocator> Deref for SmallString<N, A> {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<const N: usize, A: Allocator> DerefMut for SmallString<N, A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize, A: Allocator + Clone> Clone for SmallString<N, A> {
+    fn clone(&self) -> Self {
+        match self.heap() {
+            Some(heap) => Self::from_heap(heap.clone()),
+            None => {
+                let inline = unsafe { &self.data.inline };
+                Self {
+                    len: self.len,
+                    data: Data {
+                        inline: ManuallyDrop::new(Inline {
+                            buf: inline.buf,
+                            alloc: inline.alloc.clone(),
+                        }),
+                    },
+                }
+            }
+        }
+    }
+}
+
+impl<const N: usize, A: Allocator + Default> Default for SmallString<N, A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<const N: usize, A: Allocator> fmt::Debug for SmallString<N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize, A: Allocator> fmt::Display for SmallString<N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize, A: Allocator> fmt::Write for SmallString<N, A> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // Surface allocation failure as a formatting error instead of aborting
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize, const M: usize, A: Allocator, B: Allocator> PartialEq<SmallString<M, B>> for SmallString<N, A> {
+    fn eq(&self, other: &SmallString<M, B>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize, A: Allocator> Eq for SmallString<N, A> {}
+
+impl<const N: usize, A: Allocator> PartialEq<str> for SmallString<N, A> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize, A: Allocator> PartialEq<&str> for SmallString<N, A> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize, A: Allocator, B: Allocator> PartialEq<String<B>> for SmallString<N, A> {
+    fn eq(&self, other: &String<B>) -> bool {
+        self.as_str() == &**other
+    }
+}
+
+impl<const N: usize, A: Allocator> PartialOrd for SmallString<N, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize, A: Allocator> Ord for SmallString<N, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<const N: usize, A: Allocator> Hash for SmallString<N, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl<const N: usize, A: Allocator> AsRef<str> for SmallString<N, A> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize, A: Allocator> AsRef<[u8]> for SmallString<N, A> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize, A: Allocator> Borrow<str> for SmallString<N, A> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize, A: Allocator + Default> From<&str> for SmallString<N, A> {
+    fn from(s: &str) -> Self {
+        Self::from_str_in(s, A::default())
+    }
+}
+
+impl<const N: usize, A: Allocator> From<String<A>> for SmallString<N, A> {
+    /// Takes over the string's buffer without copying. An unallocated string starts out inline.
+    fn from(s: String<A>) -> Self {
+        if s.capacity() > 0 {
+            return Self::from_heap(s);
+        }
+        let (_, _, _, alloc) = s.into_raw_parts_with_alloc();
+        Self::new_in(alloc)
+    }
+}
+
+impl<const N: usize, A: Allocator> From<SmallString<N, A>> for String<A> {
+    fn from(s: SmallString<N, A>) -> Self {
+        s.into_string()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize, A: Allocator> serde::Serialize for SmallString<N, A> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize, A: Allocator + Default> serde::Deserialize<'de> for SmallString<N, A> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        serde::de::DeserializeSeed::deserialize(crate::de::SmallStringSeed(A::default()), deserializer)
+    }
+}
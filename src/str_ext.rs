@@ -0,0 +1,182 @@
+//! Allocator-aware versions of the allocating `str` and `[&str]` methods.
+//!
+//! `str::replace`, `str::to_lowercase`, `[&str]::join` and friends always return a `std::string::String` in the
+//! global allocator. [`StrExt`] and [`StrSliceExt`] provide `_in` variants that build a [`String<A>`] directly in the
+//! given allocator, producing the same text as their std counterparts.
+//!
+//! ```
+//! #![feature(allocator_api)]
+//!
+//! use std::alloc::Global;
+//! use string_alloc::{StrExt, StrSliceExt};
+//!
+//! let s = "Hello, World".replace_in("World", "arena", Global);
+//! assert_eq!(&*s, "Hello, arena");
+//! assert_eq!(&*["a", "b", "c"].join_in("/", Global), "a/b/c");
+//! ```
+
+use core::alloc::Allocator;
+use core::borrow::Borrow;
+use core::str::pattern::Pattern;
+
+use crate::String;
+
+/// Allocating `str` methods that take an allocator.
+///
+/// On a [`String<A>`], method resolution picks the inherent [`String::to_string_in`] over
+/// [`StrExt::to_string_in`]; use [`String::clone_in`] there instead.
+pub trait StrExt {
+    /// Copies the string slice into a new `String` in `alloc`.
+    fn to_string_in<A: Allocator>(&self, alloc: A) -> String<A>;
+
+    /// Replaces all matches of a pattern with another string.
+    ///
+    /// See [`str::replace`] for more details.
+    fn replace_in<P: Pattern, A: Allocator>(&self, from: P, to: &str, alloc: A) -> String<A>;
+
+    /// Replaces the first `count` matches of a pattern with another string.
+    ///
+    /// See [`str::replacen`] for more details.
+    fn replacen_in<P: Pattern, A: Allocator>(&self, pat: P, to: &str, count: usize, alloc: A) -> String<A>;
+
+    /// Creates a new `String` by repeating the string slice `n` times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity would overflow.
+    ///
+    /// See [`str::repeat`] for more details.
+    fn repeat_in<A: Allocator>(&self, n: usize, alloc: A) -> String<A>;
+
+    /// Returns the lowercase equivalent of the string slice, including the final sigma rule.
+    ///
+    /// Telling a word-final 'Σ' apart needs Unicode properties that `core` only exposes on nightly, so a string
+    /// containing 'Σ' is lowercased by [`str::to_lowercase`] in the global allocator first, then copied into
+    /// `alloc`. Any other string is mapped char by char straight into `alloc`.
+    ///
+    /// See [`str::to_lowercase`] for more details.
+    fn to_lowercase_in<A: Allocator>(&self, alloc: A) -> String<A>;
+
+    /// Returns the uppercase equivalent of the string slice.
+    ///
+    /// See [`str::to_uppercase`] for more details.
+    fn to_uppercase_in<A: Allocator>(&self, alloc: A) -> String<A>;
+
+    /// Escapes each char with [`char::escape_debug`], as [`str::escape_debug`] does.
+    fn escape_debug_in<A: Allocator>(&self, alloc: A) -> String<A>;
+
+    /// Escapes each char with [`char::escape_default`], as [`str::escape_default`] does.
+    fn escape_default_in<A: Allocator>(&self, alloc: A) -> String<A>;
+}
+
+impl StrExt for str {
+    fn to_string_in<A: Allocator>(&self, alloc: A) -> String<A> {
+        String::from_str_in(self, alloc)
+    }
+
+    fn replace_in<P: Pattern, A: Allocator>(&self, from: P, to: &str, alloc: A) -> String<A> {
+        let mut result = String::new_in(alloc);
+        let mut last_end = 0;
+        for (start, part) in self.match_indices(from) {
+            result.push_str(&self[last_end..start]);
+            result.push_str(to);
+            last_end = start + part.len();
+        }
+        result.push_str(&self[last_end..]);
+        result
+    }
+
+    fn replacen_in<P: Pattern, A: Allocator>(&self, pat: P, to: &str, count: usize, alloc: A) -> String<A> {
+        // Hope to reduce the times of re-allocation
+        let mut result = String::with_capacity_in(32, alloc);
+        let mut last_end = 0;
+        for (start, part) in self.match_indices(pat).take(count) {
+            result.push_str(&self[last_end..start]);
+            result.push_str(to);
+            last_end = start + part.len();
+        }
+        result.push_str(&self[last_end..]);
+        result
+    }
+
+    fn repeat_in<A: Allocator>(&self, n: usize, alloc: A) -> String<A> {
+        let capacity = self.len().checked_mul(n).expect("capacity overflow");
+        let mut result = String::with_capacity_in(capacity, alloc);
+        if capacity > 0 {
+            result.push_str(self);
+            // Double the copied prefix each time, so only O(log n) copies are made
+            while result.len() * 2 <= capacity {
+                result.extend_from_within(..);
+            }
+            result.extend_from_within(..capacity - result.len());
+        }
+        result
+    }
+
+    fn to_lowercase_in<A: Allocator>(&self, alloc: A) -> String<A> {
+        if self.contains('Σ') {
+            // Σ maps to σ, except at the end of a word where it maps to ς. This is the only conditional
+            // (contextual) but language-independent mapping in `SpecialCasing.txt`.
+            return String::from_str_in(&self.to_lowercase(), alloc);
+        }
+        let mut result = String::with_capacity_in(self.len(), alloc);
+        result.extend(self.chars().flat_map(char::to_lowercase));
+        result
+    }
+
+    fn to_uppercase_in<A: Allocator>(&self, alloc: A) -> String<A> {
+        let mut result = String::with_capacity_in(self.len(), alloc);
+        result.extend(self.chars().flat_map(char::to_uppercase));
+        result
+    }
+
+    fn escape_debug_in<A: Allocator>(&self, alloc: A) -> String<A> {
+        let mut result = String::with_capacity_in(self.len(), alloc);
+        result.extend(self.escape_debug());
+        result
+    }
+
+    fn escape_default_in<A: Allocator>(&self, alloc: A) -> String<A> {
+        let mut result = String::with_capacity_in(self.len(), alloc);
+        result.extend(self.escape_default());
+        result
+    }
+}
+
+/// Allocating `[&str]` methods that take an allocator.
+pub trait StrSliceExt {
+    /// Flattens the slice of strings into a single `String`.
+    ///
+    /// See [`slice::concat`] for more details.
+    fn concat_in<A: Allocator>(&self, alloc: A) -> String<A>;
+
+    /// Flattens the slice of strings into a single `String`, placing `sep` between each.
+    ///
+    /// See [`slice::join`] for more details.
+    fn join_in<A: Allocator>(&self, sep: &str, alloc: A) -> String<A>;
+}
+
+impl<S: Borrow<str>> StrSliceExt for [S] {
+    fn concat_in<A: Allocator>(&self, alloc: A) -> String<A> {
+        self.join_in("", alloc)
+    }
+
+    fn join_in<A: Allocator>(&self, sep: &str, alloc: A) -> String<A> {
+        let Some((first, rest)) = self.split_first() else {
+            return String::new_in(alloc);
+        };
+        let len = rest
+            .iter()
+            .try_fold(first.borrow().len(), |len, s| {
+                len.checked_add(sep.len())?.checked_add(s.borrow().len())
+            })
+            .expect("attempt to join into collection with len > usize::MAX");
+        let mut result = String::with_capacity_in(len, alloc);
+        result.push_str(first.borrow());
+        for s in rest {
+            result.push_str(sep);
+            result.push_str(s.borrow());
+        }
+        result
+    }
+}
@@ -1,18 +1,26 @@
-use alloc::vec::Vec;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::TryReserveError;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use alloc::vec::{self, Vec};
 use core::borrow::Borrow;
 use core::fmt;
 use core::hash::{Hash, Hasher};
-use core::ops::Deref;
+use core::iter::FusedIterator;
+use core::ops::{Bound, Deref, DerefMut, Index, IndexMut, Range, RangeBounds};
+use core::slice::SliceIndex;
 use core::str;
+use core::str::pattern::{Pattern, Searcher};
 
 use ::alloc::alloc::{Allocator, Global};
 
-#[derive(Debug, Clone)]
-pub struct String<A: Allocator + Clone + Default = Global> {
+#[derive(Clone)]
+pub struct String<A: Allocator = Global> {
     vec: Vec<u8, A>,
 }
 
-impl<A: Allocator + Clone + Default> String<A> {
+impl<A: Allocator> String<A> {
     /// Creates a new empty `String` with the specified allocator.
     ///
     /// See [`std::string::String::new`] for more details.
@@ -31,6 +39,15 @@ impl<A: Allocator + Clone + Default> String<A> {
         }
     }
 
+    /// Tries to create a new empty `String` with at least the specified capacity with the specified allocator.
+    ///
+    /// Returns an error instead of aborting if the allocation fails.
+    pub fn try_with_capacity_in(cap: usize, alloc: A) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            vec: Vec::try_with_capacity_in(cap, alloc)?,
+        })
+    }
+
     /// Creates a new `String` from a string slice with the specified allocator.
     ///
     /// See [`std::string::String::from_str`] for more details.
@@ -40,23 +57,197 @@ impl<A: Allocator + Clone + Default> String<A> {
         Self { vec }
     }
 
+    /// Tries to create a new `String` from a string slice with the specified allocator.
+    ///
+    /// Returns an error instead of aborting if the allocation fails.
+    pub fn try_from_str_in(s: &str, alloc: A) -> Result<Self, TryReserveError> {
+        let mut vec = Vec::try_with_capacity_in(s.len(), alloc)?;
+        vec.extend_from_slice(s.as_bytes());
+        Ok(Self { vec })
+    }
+
     /// Converts a vector of bytes to a `String` with the specified allocator.
     ///
+    /// On failure the original vector is handed back through [`FromUtf8Error::into_bytes`].
+    ///
     /// See [`std::string::String::from_utf8`] for more details.
-    pub fn from_utf8_in(vec: Vec<u8, A>) -> Result<Self, core::str::Utf8Error> {
+    pub fn from_utf8_in(vec: Vec<u8, A>) -> Result<Self, FromUtf8Error<A>> {
         match str::from_utf8(&vec) {
             Ok(_) => Ok(Self { vec }),
-            Err(e) => Err(e),
+            Err(error) => Err(FromUtf8Error { bytes: vec, error }),
+        }
+    }
+
+    /// Converts a slice of bytes to a `String` with the specified allocator, replacing invalid sequences with
+    /// `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// Unlike the standard library version this always allocates, since the result lives in `alloc`.
+    ///
+    /// See [`std::string::String::from_utf8_lossy`] for more details.
+    pub fn from_utf8_lossy_in(v: &[u8], alloc: A) -> Self {
+        let mut res = Self::with_capacity_in(v.len(), alloc);
+        for chunk in v.utf8_chunks() {
+            res.push_str(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                res.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+        res
+    }
+
+    /// Decodes a UTF-16 encoded slice into a `String` with the specified allocator.
+    ///
+    /// See [`std::string::String::from_utf16`] for more details.
+    pub fn from_utf16_in(v: &[u16], alloc: A) -> Result<Self, FromUtf16Error> {
+        Self::decode_utf16_in(v.iter().copied(), v.len(), alloc)
+    }
+
+    /// Decodes a UTF-16 encoded slice into a `String` with the specified allocator, replacing invalid data with
+    /// `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// See [`std::string::String::from_utf16_lossy`] for more details.
+    pub fn from_utf16_lossy_in(v: &[u16], alloc: A) -> Self {
+        Self::decode_utf16_lossy_in(v.iter().copied(), v.len(), alloc)
+    }
+
+    /// Decodes a UTF-16LE encoded byte slice into a `String` with the specified allocator.
+    ///
+    /// Fails if the slice has an odd length or contains unpaired surrogates.
+    ///
+    /// See [`std::string::String::from_utf16le`] for more details.
+    pub fn from_utf16le_in(v: &[u8], alloc: A) -> Result<Self, FromUtf16Error> {
+        let (chunks, []) = v.as_chunks::<2>() else {
+            return Err(FromUtf16Error(()));
+        };
+        Self::decode_utf16_in(chunks.iter().copied().map(u16::from_le_bytes), chunks.len(), alloc)
+    }
+
+    /// Decodes a UTF-16LE encoded byte slice into a `String` with the specified allocator, replacing invalid data
+    /// with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// A trailing odd byte is also replaced.
+    ///
+    /// See [`std::string::String::from_utf16le_lossy`] for more details.
+    pub fn from_utf16le_lossy_in(v: &[u8], alloc: A) -> Self {
+        let (chunks, rest) = v.as_chunks::<2>();
+        let mut res = Self::decode_utf16_lossy_in(chunks.iter().copied().map(u16::from_le_bytes), chunks.len(), alloc);
+        if !rest.is_empty() {
+            res.push(char::REPLACEMENT_CHARACTER);
+        }
+        res
+    }
+
+    /// Decodes a UTF-16BE encoded byte slice into a `String` with the specified allocator.
+    ///
+    /// Fails if the slice has an odd length or contains unpaired surrogates.
+    ///
+    /// See [`std::string::String::from_utf16be`] for more details.
+    pub fn from_utf16be_in(v: &[u8], alloc: A) -> Result<Self, FromUtf16Error> {
+        let (chunks, []) = v.as_chunks::<2>() else {
+            return Err(FromUtf16Error(()));
+        };
+        Self::decode_utf16_in(chunks.iter().copied().map(u16::from_be_bytes), chunks.len(), alloc)
+    }
+
+    /// Decodes a UTF-16BE encoded byte slice into a `String` with the specified allocator, replacing invalid data
+    /// with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// A trailing odd byte is also replaced.
+    ///
+    /// See [`std::string::String::from_utf16be_lossy`] for more details.
+    pub fn from_utf16be_lossy_in(v: &[u8], alloc: A) -> Self {
+        let (chunks, rest) = v.as_chunks::<2>();
+        let mut res = Self::decode_utf16_lossy_in(chunks.iter().copied().map(u16::from_be_bytes), chunks.len(), alloc);
+        if !rest.is_empty() {
+            res.push(char::REPLACEMENT_CHARACTER);
+        }
+        res
+    }
+
+    fn decode_utf16_in<I>(iter: I, len: usize, alloc: A) -> Result<Self, FromUtf16Error>
+    where
+        I: Iterator<Item = u16>,
+    {
+        let mut res = Self::with_capacity_in(len, alloc);
+        for ch in char::decode_utf16(iter) {
+            res.push(ch.map_err(|_| FromUtf16Error(()))?);
         }
+        Ok(res)
+    }
+
+    fn decode_utf16_lossy_in<I>(iter: I, len: usize, alloc: A) -> Self
+    where
+        I: Iterator<Item = u16>,
+    {
+        let mut res = Self::with_capacity_in(len, alloc);
+        for ch in char::decode_utf16(iter) {
+            res.push(ch.unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+        res
     }
 
     /// Converts a vector of bytes to a `String` with the specified allocator without checking that the string contains valid UTF-8.
     ///
     /// See [`std::string::String::from_utf8_unchecked`] for more details.
+    ///
+    /// # Safety
+    ///
+    /// The bytes passed in must be valid UTF-8.
     pub unsafe fn from_utf8_unchecked_in(vec: Vec<u8, A>) -> Self {
         Self { vec }
     }
 
+    /// Creates a new `String` from a pointer, a length, a capacity and an allocator.
+    ///
+    /// See [`std::string::String::from_raw_parts`] and [`Vec::from_raw_parts_in`] for more details.
+    ///
+    /// # Safety
+    ///
+    /// All the safety requirements of [`Vec::from_raw_parts_in`] apply, and the first `length` bytes at `buf`
+    /// must be valid UTF-8.
+    pub unsafe fn from_raw_parts_in(buf: *mut u8, length: usize, capacity: usize, alloc: A) -> Self {
+        Self {
+            vec: unsafe { Vec::from_raw_parts_in(buf, length, capacity, alloc) },
+        }
+    }
+
+    /// Decomposes a `String` into its raw components: `(pointer, length, capacity, allocator)`.
+    ///
+    /// The memory can be turned back into a `String` with [`String::from_raw_parts_in`].
+    ///
+    /// See [`Vec::into_raw_parts_with_alloc`] for more details.
+    pub fn into_raw_parts_with_alloc(self) -> (*mut u8, usize, usize, A) {
+        self.vec.into_raw_parts_with_alloc()
+    }
+
+    /// Converts a `String` into a byte vector, keeping its allocator.
+    ///
+    /// See [`std::string::String::into_bytes`] for more details.
+    pub fn into_bytes(self) -> Vec<u8, A> {
+        self.vec
+    }
+
+    /// Converts this `String` into a `Box<str, A>` in the same allocator, dropping any excess capacity.
+    ///
+    /// See [`std::string::String::into_boxed_str`] for more details.
+    pub fn into_boxed_str(self) -> Box<str, A> {
+        let (ptr, alloc) = Box::into_raw_with_allocator(self.vec.into_boxed_slice());
+        // `str` has the same layout as `[u8]`, and the bytes are valid UTF-8.
+        unsafe { Box::from_raw_in(ptr as *mut str, alloc) }
+    }
+
+    /// Consumes and leaks the `String`, returning a mutable reference to its contents.
+    ///
+    /// This is mainly useful with arena allocators that free everything at once and outlive the string.
+    ///
+    /// See [`std::string::String::leak`] for more details.
+    pub fn leak<'a>(self) -> &'a mut str
+    where
+        A: 'a,
+    {
+        unsafe { str::from_utf8_unchecked_mut(self.vec.leak()) }
+    }
+
     /// Appends a given string slice onto the end of this `String`.
     ///
     /// See [`std::string::String::push_str`] for more details.
@@ -64,6 +255,15 @@ impl<A: Allocator + Clone + Default> String<A> {
         self.vec.extend_from_slice(s.as_bytes());
     }
 
+    /// Tries to append a given string slice onto the end of this `String`.
+    ///
+    /// Returns an error instead of aborting if the allocation fails, leaving the string unchanged.
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(s.len())?;
+        self.vec.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+
     /// Appends the given char to the end of this `String`.
     ///
     /// See [`std::string::String::push`] for more details.
@@ -72,12 +272,20 @@ impl<A: Allocator + Clone + Default> String<A> {
         self.push_str(ch.encode_utf8(&mut buf));
     }
 
+    /// Tries to append the given char to the end of this `String`.
+    ///
+    /// Returns an error instead of aborting if the allocation fails, leaving the string unchanged.
+    pub fn try_push(&mut self, ch: char) -> Result<(), TryReserveError> {
+        let mut buf = [0; 4];
+        self.try_push_str(ch.encode_utf8(&mut buf))
+    }
+
     /// Removes the last character from the string buffer and returns it.
     ///
     /// See [`std::string::String::pop`] for more details.
     pub fn pop(&mut self) -> Option<char> {
         let s = self.deref();
-        let ch = s.chars().rev().next()?;
+        let ch = s.chars().next_back()?;
         let new_len = s.len() - ch.len_utf8();
         self.vec.truncate(new_len);
         Some(ch)
@@ -85,81 +293,358 @@ impl<A: Allocator + Clone + Default> String<A> {
 
     /// Inserts a character into this `String` at a byte position.
     ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the `String`'s length, or if it does not lie on a char boundary.
+    ///
     /// See [`std::string::String::insert`] for more details.
     pub fn insert(&mut self, idx: usize, ch: char) {
+        assert!(self.is_char_boundary(idx));
         let mut buf = [0; 4];
         let bytes = ch.encode_utf8(&mut buf);
-        let byte_idx = {
-            let s = self.deref();
-            s.char_indices()
-                .nth(idx)
-                .map(|(i, _)| i)
-                .unwrap_or_else(|| panic!("insertion index (is {}) should be <= len (is {})", idx, s.len()))
-        };
-        self.vec.splice(byte_idx..byte_idx, bytes.as_bytes().iter().cloned());
+        self.vec.splice(idx..idx, bytes.as_bytes().iter().cloned());
+    }
+
+    /// Tries to insert a character into this `String` at a byte position.
+    ///
+    /// Returns an error instead of aborting if the allocation fails, leaving the string unchanged.
+    pub fn try_insert(&mut self, idx: usize, ch: char) -> Result<(), TryReserveError> {
+        assert!(self.is_char_boundary(idx));
+        self.vec.try_reserve(ch.len_utf8())?;
+        self.insert(idx, ch);
+        Ok(())
+    }
+
+    /// Inserts a string slice into this `String` at a byte position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the `String`'s length, or if it does not lie on a char boundary.
+    ///
+    /// See [`std::string::String::insert_str`] for more details.
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        assert!(self.is_char_boundary(idx));
+        self.vec.splice(idx..idx, string.bytes());
+    }
+
+    /// Tries to insert a string slice into this `String` at a byte position.
+    ///
+    /// Returns an error instead of aborting if the allocation fails, leaving the string unchanged.
+    pub fn try_insert_str(&mut self, idx: usize, string: &str) -> Result<(), TryReserveError> {
+        assert!(self.is_char_boundary(idx));
+        self.vec.try_reserve(string.len())?;
+        self.insert_str(idx, string);
+        Ok(())
+    }
+
+    /// Inserts a character into this `String` before the `idx`-th character.
+    ///
+    /// Unlike [`String::insert`], `idx` counts characters rather than bytes, so this is O(n).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the number of characters in the `String`.
+    pub fn insert_at_char(&mut self, idx: usize, ch: char) {
+        let byte_idx = self.char_to_byte(idx).unwrap_or_else(|| {
+            panic!(
+                "insertion index (is {}) should be <= len (is {})",
+                idx,
+                self.chars().count()
+            )
+        });
+        self.insert(byte_idx, ch);
     }
 
     /// Removes a char from this `String` at a byte position and returns it.
     ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than or equal to the `String`'s length, or if it does not lie on a char boundary.
+    ///
     /// See [`std::string::String::remove`] for more details.
     pub fn remove(&mut self, idx: usize) -> char {
+        let ch = match self[idx..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string"),
+        };
+        self.vec.drain(idx..idx + ch.len_utf8());
+        ch
+    }
+
+    /// Removes the `idx`-th character from this `String` and returns it.
+    ///
+    /// Unlike [`String::remove`], `idx` counts characters rather than bytes, so this is O(n).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than or equal to the number of characters in the `String`.
+    pub fn remove_char_at(&mut self, idx: usize) -> char {
         let (start, ch) = {
             let s = self.deref();
             s.char_indices()
                 .nth(idx)
                 .unwrap_or_else(|| panic!("removal index (is {}) should be < len (is {})", idx, s.chars().count()))
         };
-        let end = start + ch.len_utf8();
-        self.vec.drain(start..end);
+        self.vec.drain(start..start + ch.len_utf8());
         ch
     }
 
     /// Splits the string into two at the given byte index.
     ///
+    /// # Panics
+    ///
+    /// Panics if `at` is not on a char boundary, or if it is beyond the last code point of the string.
+    ///
     /// See [`std::string::String::split_off`] for more details.
     pub fn split_off(&mut self, at: usize) -> Self
     where
         A: Clone,
     {
-        let byte_idx = {
-            let s = self.deref();
-            s.char_indices().nth(at).map(|(i, _)| i).unwrap_or_else(|| {
-                panic!(
-                    "split_off index (is {}) should be <= len (is {})",
-                    at,
-                    s.chars().count()
-                )
-            })
-        };
-        let vec = self.vec.split_off(byte_idx);
+        assert!(self.is_char_boundary(at));
+        let vec = self.vec.split_off(at);
         Self { vec }
     }
 
+    /// Tries to split the string into two at the given byte index.
+    ///
+    /// Returns an error instead of aborting if the allocation fails, leaving the string unchanged.
+    pub fn try_split_off(&mut self, at: usize) -> Result<Self, TryReserveError>
+    where
+        A: Clone,
+    {
+        assert!(self.is_char_boundary(at));
+        let mut vec = Vec::try_with_capacity_in(self.len() - at, self.vec.allocator().clone())?;
+        vec.extend_from_slice(&self.vec[at..]);
+        self.vec.truncate(at);
+        Ok(Self { vec })
+    }
+
+    /// Splits the string into two before the `at`-th character.
+    ///
+    /// Unlike [`String::split_off`], `at` counts characters rather than bytes, so this is O(n).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is larger than the number of characters in the `String`.
+    pub fn split_off_at_char(&mut self, at: usize) -> Self
+    where
+        A: Clone,
+    {
+        let byte_idx = self.char_to_byte(at).unwrap_or_else(|| {
+            panic!(
+                "split_off index (is {}) should be <= len (is {})",
+                at,
+                self.chars().count()
+            )
+        });
+        self.split_off(byte_idx)
+    }
+
     /// Retains only the characters specified by the predicate.
     ///
+    /// Runs in a single pass, shifting each kept char at most once. If the predicate panics, the string keeps the
+    /// chars it has not visited yet and remains valid UTF-8.
+    ///
     /// See [`std::string::String::retain`] for more details.
     pub fn retain<F>(&mut self, mut f: F)
     where
         F: FnMut(char) -> bool,
     {
-        let mut i = 0;
-        let mut len = self.len();
-        while i < len {
-            let ch = {
-                let s = self.deref();
-                match s[i..].chars().next() {
-                    Some(c) => c,
-                    None => break,
-                }
+        self.retain_mut(|ch| f(*ch))
+    }
+
+    /// Retains only the characters specified by the predicate, which may also replace each kept char.
+    ///
+    /// Replacements can have any length. One that is longer in UTF-8 than the chars removed so far takes a slower
+    /// path that shifts the unvisited rest of the string up, taking all spare capacity at once so that this happens
+    /// only `O(log n)` times. It is panic-safe in the same way as [`String::retain`].
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut char) -> bool,
+    {
+        let len = self.len();
+        let mut guard = RetainGuard {
+            vec: &mut self.vec,
+            len,
+            idx: 0,
+            deleted: 0,
+        };
+        while guard.idx < len {
+            // SAFETY: bytes from `idx` on have not been written yet, so they are still the original UTF-8, and
+            // `idx < len` means at least one char is left
+            let orig = unsafe {
+                let tail = str::from_utf8_unchecked(guard.vec.get_unchecked(guard.idx..len));
+                tail.chars().next().unwrap_unchecked()
             };
-            let ch_len = ch.len_utf8();
-            if !f(ch) {
-                self.vec.drain(i..i + ch_len);
-                len -= ch_len;
+            let mut ch = orig;
+            let keep = f(&mut ch);
+            let ch_len = orig.len_utf8();
+            if !keep {
+                guard.deleted += ch_len;
+            } else if ch == orig {
+                if guard.deleted > 0 {
+                    let at = guard.idx - guard.deleted;
+                    ch.encode_utf8(&mut guard.vec[at..]);
+                }
+            } else if ch.len_utf8() <= ch_len + guard.deleted {
+                let at = guard.idx - guard.deleted;
+                guard.deleted = guard.deleted + ch_len - ch.len_utf8();
+                ch.encode_utf8(&mut guard.vec[at..]);
             } else {
-                i += ch_len;
+                guard.finish_growing(f, ch, ch_len);
+                return;
+            }
+            guard.idx += ch_len;
+        }
+    }
+
+    /// Retains only the ASCII bytes specified by the predicate. Non-ASCII chars are always kept.
+    ///
+    /// This skips UTF-8 decoding, so it is the fastest way to strip ASCII delimiters or control characters. It is
+    /// panic-safe in the same way as [`String::retain`].
+    pub fn retain_ascii<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u8) -> bool,
+    {
+        let len = self.len();
+        let mut guard = RetainGuard {
+            vec: &mut self.vec,
+            len,
+            idx: 0,
+            deleted: 0,
+        };
+        while guard.idx < len {
+            let byte = guard.vec[guard.idx];
+            // Removing only whole ASCII chars keeps the bytes valid UTF-8
+            if byte.is_ascii() && !f(byte) {
+                guard.deleted += 1;
+            } else if guard.deleted > 0 {
+                guard.vec[guard.idx - guard.deleted] = byte;
+            }
+            guard.idx += 1;
+        }
+    }
+
+    /// Removes the specified byte range from the string in bulk, returning all removed characters as an iterator.
+    ///
+    /// The range is removed even if the iterator is not consumed until the end.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point or end point do not lie on a char boundary, or if they're out of bounds.
+    ///
+    /// See [`std::string::String::drain`] for more details.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = self.char_boundary_range(range);
+        Drain {
+            inner: self.vec.drain(range),
+        }
+    }
+
+    /// Resolves a byte range, panicking unless both ends lie on char boundaries.
+    fn char_boundary_range<R>(&self, range: R) -> Range<usize>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1).expect("attempted to index from an overflowing index"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1).expect("attempted to index to an overflowing index"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(self.is_char_boundary(start));
+        assert!(self.is_char_boundary(end));
+        start..end
+    }
+
+    /// Removes the specified byte range and replaces it with the given string, reusing the buffer.
+    ///
+    /// The replacement does not need to be the same length as the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point or end point do not lie on a char boundary, or if they're out of bounds.
+    ///
+    /// See [`std::string::String::replace_range`] for more details.
+    pub fn replace_range<R>(&mut self, range: R, replace_with: &str)
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = self.char_boundary_range(range);
+        self.vec.splice(range, replace_with.bytes());
+    }
+
+    /// Copies the bytes in `src` to the end of this `String`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point or end point do not lie on a char boundary, or if they're out of bounds.
+    ///
+    /// See [`std::string::String::extend_from_within`] for more details.
+    pub fn extend_from_within<R>(&mut self, src: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let src = self.char_boundary_range(src);
+        self.vec.extend_from_within(src);
+    }
+
+    /// Tries to copy the bytes in `src` to the end of this `String`.
+    ///
+    /// Returns an error instead of aborting if the allocation fails, leaving the string unchanged.
+    pub fn try_extend_from_within<R>(&mut self, src: R) -> Result<(), TryReserveError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let src = self.char_boundary_range(src);
+        self.vec.try_reserve(src.len())?;
+        self.vec.extend_from_within(src);
+        Ok(())
+    }
+
+    /// Removes all matches of pattern `pat` in the `String`.
+    ///
+    /// Match positions are buffered in the string's own allocator, never the global one. The string is only edited
+    /// once the search is done, so if the pattern panics it is left unchanged.
+    ///
+    /// See [`std::string::String::remove_matches`] for more details.
+    pub fn remove_matches<P: Pattern>(&mut self, pat: P)
+    where
+        A: Clone,
+    {
+        let mut matches = Vec::new_in(self.vec.allocator().clone());
+        {
+            let mut searcher = pat.into_searcher(self.as_str());
+            while let Some(m) = searcher.next_match() {
+                matches.push(m);
             }
         }
+
+        let end = self.len();
+        let mut len = 0;
+        let mut front = 0;
+        for (start, next) in matches.into_iter().chain(core::iter::once((end, end))) {
+            self.vec.copy_within(front..start, len);
+            len += start - front;
+            front = next;
+        }
+        self.vec.truncate(len);
+    }
+
+    /// Converts this `String` into an iterator over its chars, reusing the buffer.
+    ///
+    /// See [`std::string::String::into_chars`] for more details.
+    pub fn into_chars(self) -> IntoChars<A> {
+        IntoChars {
+            bytes: self.vec.into_iter(),
+        }
     }
 
     /// Ensures that this `String`'s capacity is at least `additional` bytes larger than its length.
@@ -176,6 +661,20 @@ impl<A: Allocator + Clone + Default> String<A> {
         self.vec.reserve_exact(additional);
     }
 
+    /// Tries to reserve capacity for at least `additional` bytes more than the current length.
+    ///
+    /// See [`std::string::String::try_reserve`] for more details.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(additional)
+    }
+
+    /// Tries to reserve the minimum capacity for at least `additional` bytes more than the current length.
+    ///
+    /// See [`std::string::String::try_reserve_exact`] for more details.
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.vec.try_reserve_exact(additional)
+    }
+
     /// Shrinks the capacity of this `String` to match its length.
     ///
     /// See [`std::string::String::shrink_to_fit`] for more details.
@@ -190,18 +689,48 @@ impl<A: Allocator + Clone + Default> String<A> {
         self.vec.clear();
     }
 
-    /// Shortens this `String` to the specified length.
+    /// Shortens this `String` to the specified length in bytes.
+    ///
+    /// If `new_len` is greater than or equal to the string's current length, this has no effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a char boundary.
     ///
     /// See [`std::string::String::truncate`] for more details.
     pub fn truncate(&mut self, new_len: usize) {
-        let current_len = self.chars().count();
-        if new_len > current_len {
-            panic!("truncate index (is {}) should be <= len (is {})", new_len, current_len);
+        if new_len <= self.len() {
+            assert!(self.is_char_boundary(new_len));
+            self.vec.truncate(new_len);
         }
-        let byte_idx = self.char_indices().nth(new_len).map(|(i, _)| i).unwrap_or(self.len());
+    }
+
+    /// Shortens this `String` to the specified number of characters.
+    ///
+    /// Unlike [`String::truncate`], `new_len` counts characters rather than bytes, so this is O(n).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` is larger than the number of characters in the `String`.
+    pub fn truncate_chars(&mut self, new_len: usize) {
+        let byte_idx = self.char_to_byte(new_len).unwrap_or_else(|| {
+            panic!(
+                "truncate index (is {}) should be <= len (is {})",
+                new_len,
+                self.chars().count()
+            )
+        });
         self.vec.truncate(byte_idx);
     }
 
+    /// Returns the byte offset of the `idx`-th character, or the length if `idx` equals the character count.
+    fn char_to_byte(&self, idx: usize) -> Option<usize> {
+        self.char_indices()
+            .map(|(i, _)| i)
+            .chain(core::iter::once(self.len()))
+            .nth(idx)
+    }
+
     /// Returns the length of this `String`, in bytes.
     ///
     /// See [`std::string::String::len`] for more details.
@@ -209,6 +738,13 @@ impl<A: Allocator + Clone + Default> String<A> {
         self.vec.len()
     }
 
+    /// Returns `true` if this `String` has a length of zero.
+    ///
+    /// See [`std::string::String::is_empty`] for more details.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
     /// Returns the capacity of this `String`, in bytes.
     ///
     /// See [`std::string::String::capacity`] for more details.
@@ -216,106 +752,512 @@ impl<A: Allocator + Clone + Default> String<A> {
         self.vec.capacity()
     }
 
+    /// Returns a reference to the underlying allocator.
+    ///
+    /// See [`Vec::allocator`] for more details.
+    pub fn allocator(&self) -> &A {
+        self.vec.allocator()
+    }
+
+    /// Returns a byte slice of this `String`'s contents.
+    ///
+    /// See [`std::string::String::as_bytes`] for more details.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec
+    }
+
+    /// Extracts a string slice containing the entire `String`.
+    ///
+    /// See [`std::string::String::as_str`] for more details.
+    pub fn as_str(&self) -> &str {
+        self
+    }
+
+    /// Converts this `String` into a mutable string slice.
+    ///
+    /// See [`std::string::String::as_mut_str`] for more details.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self
+    }
+
+    /// Returns a mutable reference to the contents of this `String`.
+    ///
+    /// See [`std::string::String::as_mut_vec`] for more details.
+    ///
+    /// # Safety
+    ///
+    /// The returned `Vec` allows writing bytes which are not valid UTF-8. The caller must ensure
+    /// the contents are valid UTF-8 before the borrow ends.
+    pub unsafe fn as_mut_vec(&mut self) -> &mut Vec<u8, A> {
+        &mut self.vec
+    }
+
     /// Converts the string into a new string with the specified allocator type.
     ///
     /// This method allows converting between different allocator types while preserving the string's contents.
-    pub fn to_string_in<B: Allocator + Clone + Default>(&self) -> String<B> {
+    pub fn to_string_in<B: Allocator + Default>(&self) -> String<B> {
         String::from_str_in(self, B::default())
     }
+
+    /// Tries to convert the string into a new string with the specified allocator type.
+    ///
+    /// Returns an error instead of aborting if the allocation fails.
+    pub fn try_to_string_in<B: Allocator + Default>(&self) -> Result<String<B>, TryReserveError> {
+        String::try_from_str_in(self, B::default())
+    }
+
+    /// Clones the string into a new string backed by the given allocator instance.
+    ///
+    /// Unlike [`String::to_string_in`], the target allocator does not need to implement `Default`, so this can copy
+    /// a string into a specific arena.
+    pub fn clone_in<B: Allocator>(&self, alloc: B) -> String<B> {
+        String::from_str_in(self, alloc)
+    }
+
+    /// Tries to clone the string into a new string backed by the given allocator instance.
+    ///
+    /// Returns an error instead of aborting if the allocation fails.
+    pub fn try_clone_in<B: Allocator>(&self, alloc: B) -> Result<String<B>, TryReserveError> {
+        String::try_from_str_in(self, alloc)
+    }
+
+    /// Moves the string's contents into a new string backed by the given allocator instance, freeing the original
+    /// buffer.
+    pub fn move_to_in<B: Allocator>(self, alloc: B) -> String<B> {
+        self.clone_in(alloc)
+    }
+
+    /// Tries to clone this `String` into the same allocator.
+    ///
+    /// Returns an error instead of aborting if the allocation fails.
+    pub fn try_clone(&self) -> Result<Self, TryReserveError>
+    where
+        A: Clone,
+    {
+        Self::try_from_str_in(self, self.vec.allocator().clone())
+    }
 }
 
-impl<A: Allocator + Clone + Default> Deref for String<A> {
+impl<A: Allocator> Deref for String<A> {
     type Target = str;
     fn deref(&self) -> &Self::Target {
         unsafe { str::from_utf8_unchecked(&self.vec) }
     }
 }
 
-impl<A: Allocator + Clone + Default> fmt::Display for String<A> {
+impl<A: Allocator> DerefMut for String<A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+}
+
+impl<A: Allocator, I: SliceIndex<str>> Index<I> for String<A> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.deref()[index]
+    }
+}
+
+impl<A: Allocator, I: SliceIndex<str>> IndexMut<I> for String<A> {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        &mut self.deref_mut()[index]
+    }
+}
+
+impl<A: Allocator> fmt::Display for String<A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.deref())
+        fmt::Display::fmt(self.deref(), f)
     }
 }
 
-impl<A: Allocator + Clone + Default> PartialEq<str> for String<A> {
-    fn eq(&self, other: &str) -> bool {
-        self.deref() == other
+impl<A: Allocator> fmt::Debug for String<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.deref(), f)
     }
 }
 
-impl<A: Allocator + Clone + Default> PartialEq for String<A> {
-    fn eq(&self, other: &Self) -> bool {
+impl<A: Allocator + Default> Default for String<A> {
+    /// Creates an empty `String` in `A::default()`.
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<A: Allocator + Default> str::FromStr for String<A> {
+    type Err = core::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_str_in(s, A::default()))
+    }
+}
+
+impl<A: Allocator, B: Allocator> PartialEq<String<B>> for String<A> {
+    fn eq(&self, other: &String<B>) -> bool {
         self.deref() == other.deref()
     }
 }
 
-impl<A: Allocator + Clone + Default> Eq for String<A> {}
+impl<A: Allocator> Eq for String<A> {}
 
-impl<A: Allocator + Clone + Default> PartialOrd for String<A> {
-    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        self.deref().partial_cmp(other.deref())
+impl<A: Allocator, B: Allocator> PartialOrd<String<B>> for String<A> {
+    fn partial_cmp(&self, other: &String<B>) -> Option<core::cmp::Ordering> {
+        Some(self.deref().cmp(other.deref()))
     }
 }
 
-impl<A: Allocator + Clone + Default> Ord for String<A> {
+/// Implements `PartialEq` and `PartialOrd` in both directions between `String<A>` and another string type, by
+/// comparing them as `str`.
+macro_rules! impl_cmp {
+    ([$($gen:tt)*] $lhs:ty, $rhs:ty) => {
+        impl<$($gen)*> PartialEq<$rhs> for $lhs {
+            fn eq(&self, other: &$rhs) -> bool {
+                PartialEq::eq(&self[..], &other[..])
+            }
+        }
+
+        impl<$($gen)*> PartialEq<$lhs> for $rhs {
+            fn eq(&self, other: &$lhs) -> bool {
+                PartialEq::eq(&self[..], &other[..])
+            }
+        }
+
+        impl<$($gen)*> PartialOrd<$rhs> for $lhs {
+            fn partial_cmp(&self, other: &$rhs) -> Option<core::cmp::Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
+
+        impl<$($gen)*> PartialOrd<$lhs> for $rhs {
+            fn partial_cmp(&self, other: &$lhs) -> Option<core::cmp::Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
+    };
+}
+
+impl_cmp!([A: Allocator] String<A>, str);
+impl_cmp!(['a, A: Allocator] String<A>, &'a str);
+impl_cmp!(['a, A: Allocator] String<A>, Cow<'a, str>);
+impl_cmp!([A: Allocator] String<A>, Box<str>);
+#[cfg(feature = "std")]
+impl_cmp!([A: Allocator] String<A>, std::string::String);
+
+impl<A: Allocator> Ord for String<A> {
     fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.deref().cmp(other.deref())
     }
 }
 
-impl<A: Allocator + Clone + Default> Hash for String<A> {
+impl<A: Allocator> Hash for String<A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.deref().hash(state);
     }
 }
 
-impl<A: Allocator + Clone + Default> AsRef<str> for String<A> {
+impl<A: Allocator> AsRef<str> for String<A> {
     fn as_ref(&self) -> &str {
         self.deref()
     }
 }
 
-impl<A: Allocator + Clone + Default> AsRef<[u8]> for String<A> {
+impl<A: Allocator> AsRef<[u8]> for String<A> {
     fn as_ref(&self) -> &[u8] {
         self.vec.as_ref()
     }
 }
 
-impl<A: Allocator + Clone + Default> Borrow<str> for String<A> {
+impl<A: Allocator> Borrow<str> for String<A> {
     fn borrow(&self) -> &str {
         self.deref()
     }
 }
 
-impl<A: Allocator + Clone + Default> From<&str> for String<A> {
+impl<A: Allocator + Default> From<&str> for String<A> {
     fn from(s: &str) -> Self {
         Self::from_str_in(s, A::default())
     }
 }
 
-impl<A: Allocator + Clone + Default> From<Vec<u8, A>> for String<A> {
-    fn from(vec: Vec<u8, A>) -> Self {
-        Self { vec }
+impl<A: Allocator> TryFrom<Vec<u8, A>> for String<A> {
+    type Error = FromUtf8Error<A>;
+
+    fn try_from(vec: Vec<u8, A>) -> Result<Self, Self::Error> {
+        Self::from_utf8_in(vec)
     }
 }
 
-impl<A: Allocator + Clone + Default> Into<Vec<u8, A>> for String<A> {
-    fn into(self) -> Vec<u8, A> {
-        self.vec
+impl<A: Allocator> From<String<A>> for Vec<u8, A> {
+    fn from(s: String<A>) -> Self {
+        s.vec
+    }
+}
+
+/// Finishes a retain pass when dropped, even if the predicate panics: the unvisited tail is shifted down over the
+/// deleted bytes and the length is fixed up, so the string stays valid UTF-8.
+struct RetainGuard<'a, A: Allocator> {
+    vec: &'a mut Vec<u8, A>,
+    len: usize,
+    idx: usize,
+    deleted: usize,
+}
+
+impl<A: Allocator> RetainGuard<'_, A> {
+    /// Finishes a `retain_mut` pass once replacement `ch`, for the `ch_len` bytes at `idx`, has outgrown the deleted
+    /// bytes. Kept out of line, since moving the buffer here would otherwise slow down the in-place loop.
+    #[cold]
+    #[inline(never)]
+    fn finish_growing<F>(&mut self, mut f: F, mut ch: char, mut ch_len: usize)
+    where
+        F: FnMut(&mut char) -> bool,
+    {
+        loop {
+            let new_len = ch.len_utf8();
+            if new_len > ch_len + self.deleted {
+                self.grow(new_len - ch_len - self.deleted);
+            }
+            let at = self.idx - self.deleted;
+            self.deleted = self.deleted + ch_len - new_len;
+            ch.encode_utf8(&mut self.vec[at..]);
+            self.idx += ch_len;
+
+            // Skip ahead to the next kept char
+            loop {
+                if self.idx >= self.len {
+                    return;
+                }
+                // SAFETY: as in `String::retain_mut`, bytes from `idx` on are still the original UTF-8
+                let orig = unsafe {
+                    let tail = str::from_utf8_unchecked(&self.vec[self.idx..self.len]);
+                    tail.chars().next().unwrap_unchecked()
+                };
+                ch = orig;
+                ch_len = orig.len_utf8();
+                if f(&mut ch) {
+                    break;
+                }
+                self.deleted += ch_len;
+                self.idx += ch_len;
+            }
+        }
+    }
+
+    /// Opens a gap of at least `additional` bytes in front of the read position and counts it as deleted, shifting
+    /// the unvisited tail up. The gap takes all spare capacity, so that growth doubles the buffer each time.
+    fn grow(&mut self, additional: usize) {
+        self.vec.reserve(additional);
+        let gap = self.vec.capacity() - self.len;
+        self.vec.resize(self.len + gap, 0);
+        self.vec.copy_within(self.idx..self.len, self.idx + gap);
+        self.len += gap;
+        self.idx += gap;
+        self.deleted += gap;
+    }
+}
+
+impl<A: Allocator> Drop for RetainGuard<'_, A> {
+    fn drop(&mut self) {
+        if self.deleted > 0 {
+            self.vec.copy_within(self.idx..self.len, self.idx - self.deleted);
+        }
+        self.vec.truncate(self.len - self.deleted);
+    }
+}
+
+/// A draining iterator for `String`.
+///
+/// This struct is created by [`String::drain`]. See [`std::string::Drain`] for more details.
+pub struct Drain<'a, A: Allocator = Global> {
+    inner: vec::Drain<'a, u8, A>,
+}
+
+impl<A: Allocator> Drain<'_, A> {
+    /// Returns the remaining (sub)string of this iterator as a slice.
+    ///
+    /// See [`std::string::Drain::as_str`] for more details.
+    pub fn as_str(&self) -> &str {
+        // The drained range was checked to start and end on char boundaries, and chars are only
+        // ever consumed whole.
+        unsafe { str::from_utf8_unchecked(self.inner.as_slice()) }
+    }
+}
+
+impl<A: Allocator> Iterator for Drain<'_, A> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next()?;
+        self.inner.nth(ch.len_utf8() - 1);
+        Some(ch)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.as_str().chars().size_hint()
+    }
+
+    fn last(mut self) -> Option<char> {
+        self.next_back()
+    }
+}
+
+impl<A: Allocator> DoubleEndedIterator for Drain<'_, A> {
+    fn next_back(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.inner.nth_back(ch.len_utf8() - 1);
+        Some(ch)
+    }
+}
+
+impl<A: Allocator> FusedIterator for Drain<'_, A> {}
+
+impl<A: Allocator> AsRef<str> for Drain<'_, A> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<A: Allocator> fmt::Debug for Drain<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_str()).finish()
+    }
+}
+
+/// An iterator over the chars of a `String`, owning its buffer.
+///
+/// This struct is created by [`String::into_chars`]. See [`std::string::IntoChars`] for more details.
+#[derive(Clone)]
+pub struct IntoChars<A: Allocator = Global> {
+    bytes: vec::IntoIter<u8, A>,
+}
+
+impl<A: Allocator> IntoChars<A> {
+    /// Views the remaining chars as a string slice.
+    ///
+    /// See [`std::string::IntoChars::as_str`] for more details.
+    pub fn as_str(&self) -> &str {
+        // The bytes came from a `String`, and chars are only ever consumed whole.
+        unsafe { str::from_utf8_unchecked(self.bytes.as_slice()) }
+    }
+}
+
+impl<A: Allocator> Iterator for IntoChars<A> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next()?;
+        self.bytes.nth(ch.len_utf8() - 1);
+        Some(ch)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.as_str().chars().size_hint()
+    }
+
+    fn last(mut self) -> Option<char> {
+        self.next_back()
+    }
+}
+
+impl<A: Allocator> DoubleEndedIterator for IntoChars<A> {
+    fn next_back(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.bytes.nth_back(ch.len_utf8() - 1);
+        Some(ch)
+    }
+}
+
+impl<A: Allocator> FusedIterator for IntoChars<A> {}
+
+impl<A: Allocator> fmt::Debug for IntoChars<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoChars").field(&self.as_str()).finish()
     }
 }
 
+/// A possible error value when converting a `String` from a UTF-8 byte vector.
+///
+/// Unlike [`core::str::Utf8Error`], this keeps the original vector so its allocation is not lost.
+///
+/// See [`std::string::FromUtf8Error`] for more details.
+#[derive(Clone)]
+pub struct FromUtf8Error<A: Allocator = Global> {
+    bytes: Vec<u8, A>,
+    error: str::Utf8Error,
+}
+
+impl<A: Allocator> FromUtf8Error<A> {
+    /// Returns a slice of the bytes that were attempted to convert to a `String`.
+    ///
+    /// See [`std::string::FromUtf8Error::as_bytes`] for more details.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the bytes that were attempted to convert to a `String`, in their original allocator.
+    ///
+    /// See [`std::string::FromUtf8Error::into_bytes`] for more details.
+    pub fn into_bytes(self) -> Vec<u8, A> {
+        self.bytes
+    }
+
+    /// Fetches a `Utf8Error` to get more details about the conversion failure.
+    ///
+    /// See [`std::string::FromUtf8Error::utf8_error`] for more details.
+    pub fn utf8_error(&self) -> str::Utf8Error {
+        self.error
+    }
+}
+
+impl<A: Allocator> fmt::Debug for FromUtf8Error<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromUtf8Error")
+            .field("bytes", &self.bytes)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<A: Allocator> PartialEq for FromUtf8Error<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes && self.error == other.error
+    }
+}
+
+impl<A: Allocator> Eq for FromUtf8Error<A> {}
+
+impl<A: Allocator> fmt::Display for FromUtf8Error<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl<A: Allocator> core::error::Error for FromUtf8Error<A> {}
+
+/// A possible error value when converting a `String` from a UTF-16 byte slice.
+///
+/// See [`std::string::FromUtf16Error`] for more details.
+#[derive(Debug)]
+pub struct FromUtf16Error(());
+
+impl fmt::Display for FromUtf16Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt("invalid utf-16: lone surrogate found", f)
+    }
+}
+
+impl core::error::Error for FromUtf16Error {}
+
 // Add format! macro support
-impl<A: Allocator + Clone + Default> fmt::Write for String<A> {
+impl<A: Allocator> fmt::Write for String<A> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        self.push_str(s);
-        Ok(())
+        // Surface allocation failure as a formatting error instead of aborting
+        self.try_push_str(s).map_err(|_| fmt::Error)
     }
 
     fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
         // Pre-allocate capacity based on the format string length
         let capacity = args.as_str().map_or(0, |s| s.len());
-        self.reserve(capacity);
+        self.try_reserve(capacity).map_err(|_| fmt::Error)?;
 
         // Use the standard library's write_fmt implementation
         fmt::write(self, args)
@@ -325,6 +1267,12 @@ impl<A: Allocator + Clone + Default> fmt::Write for String<A> {
 /// Creates a new `String` with the specified allocator and formats the arguments into it.
 ///
 /// This macro is similar to the standard library's `format!` macro but returns our allocator-aware `String`.
+/// It only relies on `core::fmt`, so it works in `no_std` crates. The buffer is pre-sized from the literal
+/// parts of the format string.
+///
+/// # Panics
+///
+/// Panics if a formatting trait implementation returns an error.
 ///
 /// # Examples
 ///
@@ -339,24 +1287,182 @@ impl<A: Allocator + Clone + Default> fmt::Write for String<A> {
 /// ```
 #[macro_export]
 macro_rules! format_in {
-    ($alloc:expr, $($arg:tt)*) => {{
-        use std::fmt::Write;
-        let mut s = $crate::String::new_in($alloc);
-        write!(s, $($arg)*).unwrap();
-        s
-    }};
+    ($alloc:expr, $fmt:literal $($arg:tt)*) => {
+        $crate::string::__format_in(
+            $alloc,
+            const { $crate::string::__estimated_capacity($fmt) },
+            ::core::format_args!($fmt $($arg)*),
+        )
+    };
+    ($alloc:expr, $($arg:tt)*) => {
+        $crate::string::__format_in($alloc, 0, ::core::format_args!($($arg)*))
+    };
+}
+
+/// Like [`format_in!`], but returns a `Result` instead of aborting if the allocation fails.
+///
+/// # Panics
+///
+/// Panics if a formatting trait implementation returns an error on its own.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(allocator_api)]
+/// use string_alloc::{String, try_format_in};
+/// use std::alloc::Global;
+///
+/// let name = "World";
+/// let s = try_format_in!(Global, "Hello, {}!", name).unwrap();
+/// assert_eq!(&*s, "Hello, World!");
+/// ```
+#[macro_export]
+macro_rules! try_format_in {
+    ($alloc:expr, $fmt:literal $($arg:tt)*) => {
+        $crate::string::__try_format_in(
+            $alloc,
+            const { $crate::string::__estimated_capacity($fmt) },
+            ::core::format_args!($fmt $($arg)*),
+        )
+    };
+    ($alloc:expr, $($arg:tt)*) => {
+        $crate::string::__try_format_in($alloc, 0, ::core::format_args!($($arg)*))
+    };
+}
+
+/// Estimates the output length of a format string, following `fmt::Arguments::estimated_capacity`.
+#[doc(hidden)]
+pub const fn __estimated_capacity(fmt: &str) -> usize {
+    let bytes = fmt.as_bytes();
+    let mut pieces_length = 0;
+    let mut has_args = false;
+    let mut starts_with_arg = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' if i + 1 < bytes.len() && bytes[i + 1] == b'{' => {
+                pieces_length += 1;
+                i += 2;
+            }
+            b'}' if i + 1 < bytes.len() && bytes[i + 1] == b'}' => {
+                pieces_length += 1;
+                i += 2;
+            }
+            b'{' => {
+                if i == 0 {
+                    starts_with_arg = true;
+                }
+                has_args = true;
+                while i < bytes.len() && bytes[i] != b'}' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            _ => {
+                pieces_length += 1;
+                i += 1;
+            }
+        }
+    }
+    if !has_args {
+        pieces_length
+    } else if starts_with_arg && pieces_length < 16 {
+        // If the format string starts with an argument, don't preallocate anything unless the
+        // length of the literal pieces is significant.
+        0
+    } else {
+        // There are some arguments, so any additional push will reallocate the string. To avoid
+        // that, we're "pre-doubling" the capacity here.
+        match pieces_length.checked_mul(2) {
+            Some(n) => n,
+            None => 0,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn __format_in<A: Allocator>(alloc: A, capacity: usize, args: fmt::Arguments<'_>) -> String<A> {
+    let mut s = String::with_capacity_in(capacity, alloc);
+    fmt::write(&mut PushStr(&mut s), args)
+        .expect("a formatting trait implementation returned an error when the underlying stream did not");
+    s
+}
+
+#[doc(hidden)]
+pub fn __try_format_in<A: Allocator>(
+    alloc: A, capacity: usize, args: fmt::Arguments<'_>,
+) -> Result<String<A>, TryReserveError> {
+    let mut s = String::try_with_capacity_in(capacity, alloc)?;
+    let mut writer = TryPushStr { s: &mut s, error: None };
+    if fmt::write(&mut writer, args).is_err() {
+        match writer.error {
+            Some(e) => return Err(e),
+            None => panic!("a formatting trait implementation returned an error when the underlying stream did not"),
+        }
+    }
+    Ok(s)
+}
+
+/// Infallible writer used by `format_in!`, aborting on allocation failure like `format!`.
+struct PushStr<'a, A: Allocator>(&'a mut String<A>);
+
+impl<A: Allocator> fmt::Write for PushStr<'_, A> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.push_str(s);
+        Ok(())
+    }
+}
+
+/// Fallible writer used by `try_format_in!`, remembering why the write failed.
+struct TryPushStr<'a, A: Allocator> {
+    s: &'a mut String<A>,
+    error: Option<TryReserveError>,
+}
+
+impl<A: Allocator> fmt::Write for TryPushStr<'_, A> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.s.try_push_str(s).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+impl<A: Allocator> From<Box<str, A>> for String<A> {
+    /// Converts a boxed string slice into a `String` without copying, keeping its allocator.
+    fn from(s: Box<str, A>) -> Self {
+        let (ptr, alloc) = Box::into_raw_with_allocator(s);
+        let bytes = unsafe { Box::from_raw_in(ptr as *mut [u8], alloc) };
+        Self { vec: bytes.into_vec() }
+    }
+}
+
+impl<A: Allocator> From<String<A>> for Rc<str, A> {
+    /// Copies the string into an exactly sized `Rc<str, A>` in the same allocator, freeing the original buffer.
+    fn from(s: String<A>) -> Self {
+        let (ptr, alloc) = Rc::into_raw_with_allocator(Rc::<[u8], A>::from(s.vec));
+        unsafe { Rc::from_raw_in(ptr as *const str, alloc) }
+    }
+}
+
+impl<A: Allocator + Clone> From<String<A>> for Arc<str, A> {
+    /// Copies the string into an exactly sized `Arc<str, A>` in the same allocator, freeing the original buffer.
+    fn from(s: String<A>) -> Self {
+        let (ptr, alloc) = Arc::into_raw_with_allocator(Arc::<[u8], A>::from(s.vec));
+        unsafe { Arc::from_raw_in(ptr as *const str, alloc) }
+    }
 }
 
 // Add conversions to/from std::string::String
 #[cfg(feature = "std")]
-impl<A: Allocator + Clone + Default> From<std::string::String> for String<A> {
+impl<A: Allocator + Default> From<std::string::String> for String<A> {
     fn from(s: std::string::String) -> Self {
         Self::from_str_in(&s, A::default())
     }
 }
 
 #[cfg(feature = "std")]
-impl<A: Allocator + Clone + Default> From<String<A>> for std::string::String {
+impl<A: Allocator> From<String<A>> for std::string::String {
     fn from(s: String<A>) -> Self {
         Self::from(&*s)
     }
@@ -364,7 +1470,7 @@ impl<A: Allocator + Clone + Default> From<String<A>> for std::string::String {
 
 // Add serde support
 #[cfg(feature = "serde")]
-impl<A: Allocator + Clone + Default> serde::Serialize for String<A> {
+impl<A: Allocator> serde::Serialize for String<A> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
@@ -374,17 +1480,16 @@ impl<A: Allocator + Clone + Default> serde::Serialize for String<A> {
 }
 
 #[cfg(feature = "serde")]
-impl<'de, A: Allocator + Clone + Default> serde::Deserialize<'de> for String<A> {
+impl<'de, A: Allocator + Default> serde::Deserialize<'de> for String<A> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = <&str>::deserialize(deserializer)?;
-        Ok(Self::from_str_in(s, A::default()))
+        serde::de::DeserializeSeed::deserialize(crate::de::StringSeed(A::default()), deserializer)
     }
 }
 
-impl<A: Allocator + Clone + Default> core::ops::Add<&str> for String<A> {
+impl<A: Allocator> core::ops::Add<&str> for String<A> {
     type Output = Self;
 
     fn add(mut self, other: &str) -> Self {
@@ -392,3 +1497,67 @@ impl<A: Allocator + Clone + Default> core::ops::Add<&str> for String<A> {
         self
     }
 }
+
+impl<A: Allocator> core::ops::AddAssign<&str> for String<A> {
+    fn add_assign(&mut self, other: &str) {
+        self.push_str(other);
+    }
+}
+
+impl<A: Allocator> Extend<char> for String<A> {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|ch| self.push(ch));
+    }
+}
+
+impl<'a, A: Allocator> Extend<&'a char> for String<A> {
+    fn extend<I: IntoIterator<Item = &'a char>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<'a, A: Allocator> Extend<&'a str> for String<A> {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|s| self.push_str(s));
+    }
+}
+
+impl<A: Allocator, B: Allocator> Extend<String<B>> for String<A> {
+    fn extend<I: IntoIterator<Item = String<B>>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|s| self.push_str(&s));
+    }
+}
+
+impl<A: Allocator + Default> FromIterator<char> for String<A> {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut res = Self::default();
+        res.extend(iter);
+        res
+    }
+}
+
+impl<'a, A: Allocator + Default> FromIterator<&'a char> for String<A> {
+    fn from_iter<I: IntoIterator<Item = &'a char>>(iter: I) -> Self {
+        let mut res = Self::default();
+        res.extend(iter);
+        res
+    }
+}
+
+impl<'a, A: Allocator + Default> FromIterator<&'a str> for String<A> {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut res = Self::default();
+        res.extend(iter);
+        res
+    }
+}
+
+impl<A: Allocator + Default, B: Allocator> FromIterator<String<B>> for String<A> {
+    fn from_iter<I: IntoIterator<Item = String<B>>>(iter: I) -> Self {
+        let mut res = Self::default();
+        res.extend(iter);
+        res
+    }
+}
@@ -0,0 +1,272 @@
+//! A trait abstracting over the owned string types, so parsers and formatters can be written once.
+
+use alloc::collections::TryReserveError;
+use core::alloc::Allocator;
+use core::ops::Deref;
+
+use crate::{ArrayString, CapacityError, SmallString, String};
+
+/// Common interface of owned, growable string types.
+///
+/// Implemented for [`String<A>`], [`SmallString<N, A>`], [`ArrayString<N>`] and, with the `std` feature,
+/// `std::string::String`. Generic code takes the backing store's construction handle as [`StringLike::Alloc`]:
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use std::alloc::Global;
+/// use string_alloc::{ArrayString, String, StringLike};
+///
+/// fn join<S: StringLike>(parts: &[&str], alloc: S::Alloc) -> S {
+///     let mut out = S::with_capacity_in(parts.iter().map(|p| p.len() + 1).sum(), alloc);
+///     for (i, part) in parts.iter().enumerate() {
+///         if i > 0 {
+///             out.push(',');
+///         }
+///         out.push_str(part);
+///     }
+///     out
+/// }
+///
+/// let s: String<Global> = join(&["a", "b"], Global);
+/// let a: ArrayString<8> = join(&["a", "b"], ());
+/// assert_eq!(s.as_str(), a.as_str());
+/// ```
+pub trait StringLike: Deref<Target = str> + Sized {
+    /// The handle needed to construct the string: an allocator, or `()` for types that don't take one.
+    type Alloc;
+
+    /// The error returned by fallible appends.
+    type Error;
+
+    /// Creates a new empty string.
+    fn new_in(alloc: Self::Alloc) -> Self;
+
+    /// Creates a new empty string with at least the specified capacity.
+    ///
+    /// # Panics
+    ///
+    /// Fixed-capacity types panic if `cap` exceeds their capacity.
+    fn with_capacity_in(cap: usize, alloc: Self::Alloc) -> Self;
+
+    /// Creates a new string from a string slice.
+    ///
+    /// # Panics
+    ///
+    /// Fixed-capacity types panic if `s` does not fit.
+    fn from_str_in(s: &str, alloc: Self::Alloc) -> Self {
+        let mut res = Self::with_capacity_in(s.len(), alloc);
+        res.push_str(s);
+        res
+    }
+
+    /// Appends a given string slice onto the end of this string.
+    ///
+    /// # Panics
+    ///
+    /// Fixed-capacity types panic if `s` does not fit. Use [`StringLike::try_push_str`] to handle that case.
+    fn push_str(&mut self, s: &str);
+
+    /// Tries to append a given string slice, leaving the string unchanged on failure.
+    fn try_push_str(&mut self, s: &str) -> Result<(), Self::Error>;
+
+    /// Appends the given char to the end of this string.
+    fn push(&mut self, ch: char) {
+        let mut buf = [0; 4];
+        self.push_str(ch.encode_utf8(&mut buf));
+    }
+
+    /// Ensures that the capacity is at least `additional` bytes larger than the length.
+    ///
+    /// # Panics
+    ///
+    /// Fixed-capacity types panic if the capacity cannot hold `additional` more bytes.
+    fn reserve(&mut self, additional: usize);
+
+    /// Shortens this string to the specified length in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a char boundary.
+    fn truncate(&mut self, new_len: usize);
+
+    /// Truncates this string, removing all contents.
+    fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Extracts a string slice containing the entire string.
+    fn as_str(&self) -> &str;
+
+    /// Returns the capacity of this string, in bytes.
+    fn capacity(&self) -> usize;
+}
+
+impl<A: Allocator> StringLike for String<A> {
+    type Alloc = A;
+    type Error = TryReserveError;
+
+    fn new_in(alloc: A) -> Self {
+        String::new_in(alloc)
+    }
+
+    fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        String::with_capacity_in(cap, alloc)
+    }
+
+    fn from_str_in(s: &str, alloc: A) -> Self {
+        String::from_str_in(s, alloc)
+    }
+
+    fn push_str(&mut self, s: &str) {
+        String::push_str(self, s)
+    }
+
+    fn try_push_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        String::try_push_str(self, s)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        String::reserve(self, additional)
+    }
+
+    fn truncate(&mut self, new_len: usize) {
+        String::truncate(self, new_len)
+    }
+
+    fn as_str(&self) -> &str {
+        String::as_str(self)
+    }
+
+    fn capacity(&self) -> usize {
+        String::capacity(self)
+    }
+}
+
+impl<const N: usize, A: Allocator> StringLike for SmallString<N, A> {
+    type Alloc = A;
+    type Error = TryReserveError;
+
+    fn new_in(alloc: A) -> Self {
+        SmallString::new_in(alloc)
+    }
+
+    fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        SmallString::with_capacity_in(cap, alloc)
+    }
+
+    fn from_str_in(s: &str, alloc: A) -> Self {
+        SmallString::from_str_in(s, alloc)
+    }
+
+    fn push_str(&mut self, s: &str) {
+        SmallString::push_str(self, s)
+    }
+
+    fn try_push_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        SmallString::try_push_str(self, s)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        SmallString::reserve(self, additional)
+    }
+
+    fn truncate(&mut self, new_len: usize) {
+        SmallString::truncate(self, new_len)
+    }
+
+    fn as_str(&self) -> &str {
+        SmallString::as_str(self)
+    }
+
+    fn capacity(&self) -> usize {
+        SmallString::capacity(self)
+    }
+}
+
+impl<const N: usize> StringLike for ArrayString<N> {
+    type Alloc = ();
+    type Error = CapacityError;
+
+    fn new_in(_alloc: ()) -> Self {
+        ArrayString::new()
+    }
+
+    fn with_capacity_in(cap: usize, _alloc: ()) -> Self {
+        assert!(cap <= N, "capacity (is {}) should be <= {}", cap, N);
+        ArrayString::new()
+    }
+
+    fn push_str(&mut self, s: &str) {
+        ArrayString::push_str(self, s).expect("ArrayString capacity exceeded")
+    }
+
+    fn try_push_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        ArrayString::push_str(self, s)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        assert!(
+            additional <= self.remaining_capacity(),
+            "reserve (is {}) should be <= remaining capacity (is {})",
+            additional,
+            self.remaining_capacity()
+        );
+    }
+
+    fn truncate(&mut self, new_len: usize) {
+        ArrayString::truncate(self, new_len)
+    }
+
+    fn as_str(&self) -> &str {
+        ArrayString::as_str(self)
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+#[cfg(feature = "std")]
+impl StringLike for std::string::String {
+    type Alloc = ();
+    type Error = TryReserveError;
+
+    fn new_in(_alloc: ()) -> Self {
+        std::string::String::new()
+    }
+
+    fn with_capacity_in(cap: usize, _alloc: ()) -> Self {
+        std::string::String::with_capacity(cap)
+    }
+
+    fn from_str_in(s: &str, _alloc: ()) -> Self {
+        s.into()
+    }
+
+    fn push_str(&mut self, s: &str) {
+        std::string::String::push_str(self, s)
+    }
+
+    fn try_push_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.try_reserve(s.len())?;
+        std::string::String::push_str(self, s);
+        Ok(())
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        std::string::String::reserve(self, additional)
+    }
+
+    fn truncate(&mut self, new_len: usize) {
+        std::string::String::truncate(self, new_len)
+    }
+
+    fn as_str(&self) -> &str {
+        std::string::String::as_str(self)
+    }
+
+    fn capacity(&self) -> usize {
+        std::string::String::capacity(self)
+    }
+}
@@ -0,0 +1,131 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::hash_of;
+use std::alloc::{AllocError, Allocator, Layout};
+use std::cell::{Cell, UnsafeCell};
+use std::ptr::NonNull;
+use string_alloc::{format_in, String};
+
+/// A fixed-size bump arena. Strings borrow it through `&Arena`, which has no `Default`.
+struct Arena {
+    buf: UnsafeCell<[u8; 4096]>,
+    used: Cell<usize>,
+}
+
+impl Arena {
+    fn new() -> Self {
+        Self {
+            buf: UnsafeCell::new([0; 4096]),
+            used: Cell::new(0),
+        }
+    }
+}
+
+unsafe impl Allocator for &Arena {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let base = self.buf.get() as *mut u8;
+        let start = (base as usize + self.used.get()).next_multiple_of(layout.align()) - base as usize;
+        let end = start.checked_add(layout.size()).ok_or(AllocError)?;
+        if end > 4096 {
+            return Err(AllocError);
+        }
+        self.used.set(end);
+        let ptr = unsafe { NonNull::new_unchecked(base.add(start)) };
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+}
+
+#[test]
+fn test_arena_string_basics() {
+    let arena = Arena::new();
+    let mut s = String::from_str_in("hello", &arena);
+    s.push_str(", world");
+    s.push('!');
+    assert_eq!(&*s, "hello, world!");
+    assert!(arena.used.get() >= s.len());
+
+    // Traits work without `Default`
+    let t = String::from_str_in("hello, world!", &arena);
+    assert!(s == t);
+    assert!(s <= t);
+    assert_eq!(hash_of(&s), hash_of("hello, world!"));
+    assert_eq!(format!("{}", s), "hello, world!");
+    let borrowed: &str = s.as_ref();
+    assert_eq!(borrowed, "hello, world!");
+
+    // Splitting clones the `&Arena` handle
+    let tail = s.split_off(5);
+    assert_eq!(&*tail, ", world!");
+    let drained: std::string::String = s.drain(1..4).collect();
+    assert_eq!(drained, "ell");
+    assert_eq!(&*s, "ho");
+}
+
+#[test]
+fn test_arena_formatting_and_errors() {
+    let arena = Arena::new();
+    let s = format_in!(&arena, "{}-{}", 1, 2);
+    assert_eq!(&*s, "1-2");
+
+    let mut bytes = Vec::new_in(&arena);
+    bytes.extend_from_slice(b"\xFF");
+    let Err(err) = String::from_utf8_in(bytes) else {
+        panic!("invalid UTF-8 accepted")
+    };
+    assert_eq!(err.into_bytes(), [0xFF]);
+
+    let mut s = String::new_in(&arena);
+    assert!(s.try_reserve(8192).is_err());
+    s.try_push_str("fits").unwrap();
+    assert_eq!(&*s, "fits");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_arena_deserialize_seed() {
+    use serde::de::DeserializeSeed;
+    use string_alloc::de::VecSeed;
+
+    let arena = Arena::new();
+    let mut de = serde_json::Deserializer::from_str(r#"["a\tb", "c"]"#);
+    let strings = VecSeed(&arena).deserialize(&mut de).unwrap();
+    assert_eq!(&*strings[0], "a\tb");
+    assert_eq!(&*strings[1], "c");
+    assert_eq!(serde_json::to_string(&strings[0]).unwrap(), r#""a\tb""#);
+}
+
+#[test]
+fn test_arena_leak_and_allocator() {
+    let arena = Arena::new();
+    let leaked: &mut str = {
+        let s = String::from_str_in("outlives the string", &arena);
+        assert!(std::ptr::eq(*s.allocator(), &arena));
+        s.leak()
+    };
+    leaked.make_ascii_uppercase();
+    assert_eq!(leaked, "OUTLIVES THE STRING");
+}
+
+#[test]
+fn test_arena_smart_pointers() {
+    let arena = Arena::new();
+    let boxed = String::from_str_in("boxed", &arena).into_boxed_str();
+    assert!(std::ptr::eq(*Box::allocator(&boxed), &arena));
+
+    let rc: std::rc::Rc<str, &Arena> = String::from_str_in("counted", &arena).into();
+    assert!(std::ptr::eq(*std::rc::Rc::allocator(&rc), &arena));
+    assert_eq!(&*rc, "counted");
+}
+
+#[test]
+fn test_arena_remove_matches() {
+    let arena = Arena::new();
+    let mut s = String::from_str_in(&"a-b-".repeat(10), &arena);
+    s.remove_matches('-');
+    s.remove_matches("ab");
+    assert!(s.is_empty());
+}
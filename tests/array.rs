@@ -0,0 +1,125 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::hash_of;
+use std::alloc::Global;
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use string_alloc::{format_array, ArrayString, CapacityError, CloneIn, String};
+
+#[test]
+fn test_capacity_errors() {
+    let mut s = ArrayString::<6>::new();
+    assert_eq!(s.capacity(), 6);
+    s.push_str("ab").unwrap();
+    s.push('中').unwrap();
+    assert_eq!(s.len(), 5);
+    assert_eq!(s.remaining_capacity(), 1);
+
+    // Failed pushes leave the string unchanged
+    assert!(s.push('é').is_err());
+    assert_eq!(s.push_str("cd").unwrap_err().to_string(), "insufficient capacity");
+    assert!(s.insert(0, 'é').is_err());
+    assert_eq!(&*s, "ab中");
+
+    s.push('!').unwrap();
+    assert!(s.is_full());
+}
+
+#[test]
+fn test_editing() {
+    let mut s: ArrayString<16> = "hé!".parse().unwrap();
+    s.insert(1, '-').unwrap();
+    assert_eq!(&*s, "h-é!");
+    assert_eq!(s.remove(2), 'é');
+    assert_eq!(s.pop(), Some('!'));
+    assert_eq!(&*s, "h-");
+
+    s.push_str("a1b2").unwrap();
+    s.retain(|c| !c.is_ascii_digit());
+    assert_eq!(&*s, "h-ab");
+    s.make_ascii_uppercase();
+    assert_eq!(&*s, "H-AB");
+    s.truncate(1);
+    assert_eq!(&*s, "H");
+    s.clear();
+    assert!(s.is_empty());
+}
+
+#[test]
+fn test_retain_panic_safety() {
+    let mut s: ArrayString<16> = "aé1🦀b2ç".parse().unwrap();
+    s.retain(|c| !c.is_ascii_digit());
+    assert_eq!(&*s, "aé🦀bç");
+
+    // A panicking predicate leaves the chars kept so far
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        s.retain(|c| {
+            assert_ne!(c, 'b', "predicate panicked");
+            c != 'é'
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(&*s, "a🦀");
+}
+
+#[test]
+#[should_panic(expected = "is_char_boundary")]
+fn test_truncate_not_char_boundary() {
+    let mut s = ArrayString::<4>::try_from("é").unwrap();
+    s.truncate(1);
+}
+
+#[test]
+fn test_formatting() {
+    let s = format_array!(16, "irq {} @ {:#x}", 7, 255).unwrap();
+    assert_eq!(&*s, "irq 7 @ 0xff");
+    let _: ArrayString<16> = s;
+    let err: CapacityError = format_array!(4, "{}", 123456).unwrap_err();
+    assert_eq!(err, "toolong".parse::<ArrayString<4>>().unwrap_err());
+
+    let mut w = ArrayString::<4>::new();
+    assert!(write!(w, "{}", 12345).is_err());
+    write!(w, "{:>4}", 1).unwrap();
+    assert_eq!(format!("[{}] {:?}", w, w), "[   1] \"   1\"");
+}
+
+#[test]
+fn test_traits_and_conversions() {
+    let a = ArrayString::<8>::try_from("same").unwrap();
+    let b = ArrayString::<16>::try_from("same").unwrap();
+    assert_eq!(a, b);
+    assert_eq!(a, "same");
+    assert_eq!(a, String::from_str_in("same", Global));
+    assert_eq!(hash_of(&a), hash_of("same"));
+    let borrowed: &str = a.borrow();
+    assert_eq!(borrowed, "same");
+
+    let set: BTreeSet<ArrayString<8>> = ["b", "a", "c"].iter().map(|s| s.parse().unwrap()).collect();
+    assert!(set.contains("a"));
+    assert_eq!(set.iter().map(|s| s.as_str()).collect::<Vec<_>>(), ["a", "b", "c"]);
+
+    // Copy semantics
+    let c = a;
+    assert_eq!(c, a);
+
+    let heap: String<Global> = a.clone_in(Global);
+    assert_eq!(&*heap, "same");
+    let heap: String<Global> = CloneIn::clone_in(&a, Global);
+    assert_eq!(&*heap, "same");
+    let heap: String<Global> = a.into();
+    assert_eq!(&*heap, "same");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let s: ArrayString<8> = serde_json::from_str(r#""fits""#).unwrap();
+    assert_eq!(&*s, "fits");
+    assert_eq!(serde_json::to_string(&s).unwrap(), r#""fits""#);
+
+    let err = serde_json::from_str::<ArrayString<4>>(r#""too long""#).unwrap_err();
+    assert!(err.to_string().contains("a string of at most 4 bytes"));
+}
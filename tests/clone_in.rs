@@ -0,0 +1,62 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::Counting;
+use std::alloc::Global;
+use string_alloc::{CloneIn, String};
+
+#[test]
+fn test_clone_in() {
+    let a = Counting::default();
+    let b = Counting::default();
+    let s = String::from_str_in("tenant data", a.clone());
+
+    let copy = s.clone_in(b.clone());
+    assert_eq!(&*copy, "tenant data");
+    assert_eq!((a.live(), b.live()), (1, 1));
+
+    let copy2 = s.try_clone_in(b.clone()).unwrap();
+    assert_eq!(&*copy2, "tenant data");
+    assert_eq!(b.live(), 2);
+}
+
+#[test]
+fn test_move_to_in() {
+    let a = Counting::default();
+    let b = Counting::default();
+    let s = String::from_str_in("moving house", a.clone());
+
+    let moved = s.move_to_in(b.clone());
+    assert_eq!(&*moved, "moving house");
+    assert_eq!((a.live(), b.live()), (0, 1));
+}
+
+#[test]
+fn test_clone_in_trait_containers() {
+    let a = Counting::default();
+    let b = Counting::default();
+
+    let mut vec = Vec::new_in(a.clone());
+    vec.push(String::from_str_in("x", a.clone()));
+    vec.push(String::from_str_in("y", a.clone()));
+    let copy = CloneIn::clone_in(&vec, b.clone());
+    assert_eq!(copy.iter().map(|s| &**s).collect::<Vec<_>>(), ["x", "y"]);
+    // One allocation for the vector and one per element
+    assert_eq!(b.live(), 3);
+    drop(vec);
+    assert_eq!(a.live(), 0);
+
+    let nested = (
+        Some(String::from_str_in("opt", Global)),
+        None::<String>,
+        [String::from_str_in("p", Global), String::from_str_in("q", Global)],
+    );
+    let copy = nested.clone_in(b.clone());
+    assert_eq!(copy.0.as_deref(), Some("opt"));
+    assert!(copy.1.is_none());
+    assert_eq!([&*copy.2[0], &*copy.2[1]], ["p", "q"]);
+    assert_eq!(b.live(), 6);
+    drop(copy);
+    assert_eq!(b.live(), 3);
+}
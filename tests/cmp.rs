@@ -0,0 +1,101 @@
+#![feature(allocator_api)]
+
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::ptr::NonNull;
+use string_alloc::String;
+
+/// A distinct allocator type, so comparisons across allocators are exercised.
+#[derive(Debug, Clone, Copy, Default)]
+struct Other;
+
+unsafe impl Allocator for Other {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+#[test]
+fn test_eq_matrix() {
+    let s = String::from_str_in("abc", Global);
+
+    assert!(s == *"abc");
+    assert!(*"abc" == s);
+    assert!(s == "abc");
+    assert!("abc" == s);
+    assert!(s != "abd");
+    assert!("abd" != s);
+
+    let cow: Cow<'_, str> = Cow::Borrowed("abc");
+    assert!(s == cow);
+    assert!(cow == s);
+    let owned: Cow<'_, str> = Cow::Owned("abc".into());
+    assert!(s == owned);
+
+    let boxed: Box<str> = "abc".into();
+    assert!(s == boxed);
+    assert!(boxed == s);
+
+    let other = String::from_str_in("abc", Other);
+    assert!(s == other);
+    assert!(other == s);
+    assert!(other != String::from_str_in("ab", Global));
+
+    #[cfg(feature = "std")]
+    {
+        let std = std::string::String::from("abc");
+        assert!(s == std);
+        assert!(std == s);
+    }
+}
+
+#[test]
+fn test_ord_matrix() {
+    let s = String::from_str_in("b", Global);
+
+    assert!(s > *"a");
+    assert!(*"c" > s);
+    assert!(s < "c");
+    assert!("a" < s);
+    assert_eq!(s.partial_cmp("b"), Some(Ordering::Equal));
+
+    let cow: Cow<'_, str> = Cow::Borrowed("a");
+    assert!(s > cow);
+    assert!(cow < s);
+
+    let boxed: Box<str> = "c".into();
+    assert!(s < boxed);
+    assert!(boxed > s);
+
+    let other = String::from_str_in("bb", Other);
+    assert!(s < other);
+    assert!(other > s);
+    assert_eq!(s.partial_cmp(&String::from_str_in("b", Other)), Some(Ordering::Equal));
+
+    #[cfg(feature = "std")]
+    {
+        let std = std::string::String::from("a");
+        assert!(s > std);
+        assert!(std < s);
+    }
+}
+
+#[test]
+fn test_ordering_matches_str() {
+    let words = ["", "a", "ab", "b", "ñ", "🦀", "Z"];
+    for x in words {
+        for y in words {
+            let sx = String::from_str_in(x, Global);
+            let sy = String::from_str_in(y, Other);
+            assert_eq!(sx.partial_cmp(&sy), x.partial_cmp(y));
+            assert_eq!(sx.partial_cmp(y), x.partial_cmp(y));
+            assert_eq!(x.partial_cmp(&sy), x.partial_cmp(y));
+            assert_eq!(sx == sy, x == y);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Helpers shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+/// Counts allocations in counters shared between clones, so tests can tell which instance a value was allocated
+/// in and check that nothing reaches the allocator unexpectedly.
+#[derive(Debug, Clone, Default)]
+pub struct Counting(Rc<Counts>);
+
+#[derive(Debug, Default)]
+struct Counts {
+    live: Cell<usize>,
+    total: Cell<usize>,
+}
+
+impl Counting {
+    /// Returns the number of allocations made through this instance, or its clones, that are still live.
+    pub fn live(&self) -> usize {
+        self.0.live.get()
+    }
+
+    /// Returns the number of allocations ever made through this instance or its clones. Every reallocation
+    /// counts, since growing goes through `allocate`.
+    pub fn total(&self) -> usize {
+        self.0.total.get()
+    }
+}
+
+unsafe impl Allocator for Counting {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.live.set(self.0.live.get() + 1);
+        self.0.total.set(self.0.total.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.0.live.set(self.0.live.get() - 1);
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+/// Hashes a value with the standard library's default hasher.
+pub fn hash_of<T: Hash + ?Sized>(v: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    v.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,98 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::hash_of;
+use std::alloc::Global;
+use std::collections::HashSet;
+use string_alloc::{CowStr, String};
+
+/// Returns the input unchanged when there is nothing to unescape, like a parser would.
+fn unescape(input: &str) -> CowStr<'_, Global> {
+    if !input.contains('\\') {
+        return CowStr::Borrowed(input);
+    }
+    let mut out = String::with_capacity_in(input.len(), Global);
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push(chars.next().unwrap_or('\\')),
+            c => out.push(c),
+        }
+    }
+    CowStr::Owned(out)
+}
+
+#[test]
+fn test_borrowed_or_owned() {
+    let plain = unescape("plain");
+    assert!(plain.is_borrowed());
+    assert_eq!(&*plain, "plain");
+
+    let escaped = unescape(r"a\\b\tc");
+    assert!(escaped.is_owned());
+    assert_eq!(escaped.as_str(), r"a\btc");
+    assert_eq!(escaped.len(), 5);
+}
+
+#[test]
+fn test_to_mut_and_into_owned() {
+    let mut cow: CowStr<'_, Global> = CowStr::from("hello");
+    cow.to_mut().push_str(" world");
+    assert!(cow.is_owned());
+    assert_eq!(cow, "hello world");
+
+    let mut cow: CowStr<'_, Global> = CowStr::Borrowed("in");
+    cow.to_mut_in(Global).push('!');
+    assert_eq!(cow, "in!");
+
+    let owned: String<Global> = CowStr::<Global>::Borrowed("copy").into_owned();
+    assert_eq!(&*owned, "copy");
+    let owned = CowStr::Owned(String::from_str_in("kept", Global)).into_owned_in(Global);
+    assert_eq!(&*owned, "kept");
+}
+
+#[test]
+fn test_traits() {
+    let borrowed: CowStr<'_, Global> = CowStr::Borrowed("same");
+    let owned: CowStr<'_, Global> = CowStr::Owned(String::from_str_in("same", Global));
+    assert_eq!(borrowed, owned);
+    assert_eq!(hash_of(&borrowed), hash_of(&owned));
+    assert_eq!(hash_of(&borrowed), hash_of("same"));
+    assert_eq!(owned, String::from_str_in("same", Global));
+    assert!(CowStr::<Global>::Borrowed("a") < CowStr::Borrowed("b"));
+
+    assert_eq!(format!("{}", borrowed), "same");
+    assert_eq!(format!("{:>6}", owned), "  same");
+    assert_eq!(format!("{:?}", owned), "\"same\"");
+
+    let set: HashSet<CowStr<'_, Global>> = [borrowed.clone(), owned.clone()].into_iter().collect();
+    assert_eq!(set.len(), 1);
+    assert!(set.contains("same"));
+
+    assert_eq!(CowStr::<Global>::default(), "");
+    let s = String::from_str_in("ref", Global);
+    assert!(CowStr::from(&s).is_borrowed());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_borrows_when_possible() {
+    use serde::de::DeserializeSeed;
+    use string_alloc::de::CowStrSeed;
+
+    let cow: CowStr<'_, Global> = serde_json::from_str(r#""borrowed""#).unwrap();
+    assert!(cow.is_borrowed());
+    assert_eq!(cow, "borrowed");
+
+    let cow: CowStr<'_, Global> = serde_json::from_str(r#""esc\"aped""#).unwrap();
+    assert!(cow.is_owned());
+    assert_eq!(cow, "esc\"aped");
+
+    // Readers never lend out borrowed strings, so the seed copies into its allocator
+    let mut de = serde_json::Deserializer::from_reader(r#""streamed""#.as_bytes());
+    let cow = CowStrSeed(Global).deserialize(&mut de).unwrap();
+    assert!(cow.is_owned());
+
+    assert_eq!(serde_json::to_string(&cow).unwrap(), r#""streamed""#);
+}
@@ -0,0 +1,115 @@
+#![feature(allocator_api)]
+
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::fmt::Write;
+use std::ptr::NonNull;
+use string_alloc::{try_format_in, String};
+
+/// An allocator that refuses any single allocation larger than `CAP` bytes.
+#[derive(Debug, Clone, Copy, Default)]
+struct CappedAlloc<const CAP: usize>;
+
+unsafe impl<const CAP: usize> Allocator for CappedAlloc<CAP> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.size() > CAP {
+            return Err(AllocError);
+        }
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+type Capped = CappedAlloc<16>;
+
+#[test]
+fn test_try_construction() {
+    let s = String::try_with_capacity_in(16, Capped::default()).unwrap();
+    assert!(s.capacity() >= 16);
+    assert!(String::try_with_capacity_in(17, Capped::default()).is_err());
+
+    let s = String::try_from_str_in("hello", Capped::default()).unwrap();
+    assert_eq!(&*s, "hello");
+    assert!(String::try_from_str_in("this is longer than sixteen", Capped::default()).is_err());
+}
+
+#[test]
+fn test_try_reserve() {
+    let mut s = String::new_in(Capped::default());
+    s.try_reserve(10).unwrap();
+    assert!(s.capacity() >= 10);
+    s.try_reserve_exact(16).unwrap();
+    assert!(s.try_reserve(17).is_err());
+    assert!(s.try_reserve_exact(usize::MAX).is_err());
+    assert!(s.is_empty());
+}
+
+#[test]
+fn test_try_append() {
+    let mut s = String::try_with_capacity_in(16, Capped::default()).unwrap();
+    s.try_push_str("0123456789").unwrap();
+    s.try_push('a').unwrap();
+    assert_eq!(&*s, "0123456789a");
+
+    // Failure leaves the string untouched
+    assert!(s.try_push_str("bcdefgh").is_err());
+    assert_eq!(&*s, "0123456789a");
+
+    s.try_push_str("bcdef").unwrap();
+    assert_eq!(s.len(), 16);
+    assert!(s.try_push('🦀').is_err());
+    assert_eq!(&*s, "0123456789abcdef");
+}
+
+#[test]
+fn test_try_insert() {
+    let mut s = String::try_from_str_in("héllo", Capped::default()).unwrap();
+    s.shrink_to_fit();
+    s.try_insert(3, '-').unwrap();
+    assert_eq!(&*s, "hé-llo");
+
+    let mut full = String::try_from_str_in("0123456789abcdef", Capped::default()).unwrap();
+    assert!(full.try_insert(0, 'x').is_err());
+    assert_eq!(&*full, "0123456789abcdef");
+}
+
+#[test]
+fn test_try_clone_and_convert() {
+    let s = String::try_from_str_in("clone me", Capped::default()).unwrap();
+    let c = s.try_clone().unwrap();
+    assert_eq!(s, c);
+
+    let mut right = String::try_from_str_in("split here", Capped::default()).unwrap();
+    let tail = right.try_split_off(5).unwrap();
+    assert_eq!(&*right, "split");
+    assert_eq!(&*tail, " here");
+
+    let big = String::from_str_in("this is longer than sixteen", Global);
+    assert!(big.try_to_string_in::<Capped>().is_err());
+    let small = String::from_str_in("fits", Global);
+    let moved: String<Capped> = small.try_to_string_in().unwrap();
+    assert_eq!(&*moved, "fits");
+}
+
+#[test]
+fn test_try_format() {
+    let mut s = String::try_with_capacity_in(16, Capped::default()).unwrap();
+    let word = "abc";
+    write!(s, "{}-{}", 12345, word).unwrap();
+    assert_eq!(&*s, "12345-abc");
+
+    // Allocation failure surfaces as a formatting error
+    let long = "0123456789";
+    assert!(write!(s, "{}", long).is_err());
+}
+
+#[test]
+fn test_try_format_macro() {
+    let s = try_format_in!(Capped::default(), "{}-{}", 12345, 'x').unwrap();
+    assert_eq!(&*s, "12345-x");
+
+    let long = "0123456789abcdefg";
+    assert!(try_format_in!(Capped::default(), "{}", long).is_err());
+}
@@ -1,8 +1,9 @@
 #![feature(allocator_api)]
+#![allow(clippy::write_literal)]
 
 use std::alloc::Global;
 use std::fmt::Write;
-use string_alloc::{format_in, String};
+use string_alloc::{format_in, try_format_in, String};
 
 #[test]
 fn test_format_macro() {
@@ -39,3 +40,29 @@ fn test_format_macro_direct() {
     assert_eq!(&*s3, "你好，世界！");
 }
 
+#[test]
+fn test_format_macro_presizes() {
+    let s = format_in!(Global, "a fairly long literal prefix: {}", 1);
+    assert_eq!(&*s, "a fairly long literal prefix: 1");
+    // With arguments the literal length is pre-doubled
+    assert_eq!(s.capacity(), 2 * "a fairly long literal prefix: ".len());
+
+    // Without arguments the literal length is exact
+    let s = format_in!(Global, "no arguments {{here}}");
+    assert_eq!(&*s, "no arguments {here}");
+    assert_eq!(s.capacity(), "no arguments {here}".len());
+
+    let value = 42;
+    let s = format_in!(Global, "{value:>5}|{:<3}|", 'x');
+    assert_eq!(&*s, "   42|x  |");
+}
+
+#[test]
+fn test_try_format_macro() {
+    let name = "World";
+    let s = try_format_in!(Global, "Hello, {}!", name).unwrap();
+    assert_eq!(&*s, "Hello, World!");
+
+    let s = try_format_in!(Global, "{}-{}", 1, 2).unwrap();
+    assert_eq!(&*s, "1-2");
+}
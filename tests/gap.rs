@@ -0,0 +1,110 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::Counting;
+use std::alloc::Global;
+use std::fmt::Write;
+use string_alloc::{GapString, String};
+
+#[test]
+fn test_edit_at_cursor() {
+    let mut s = GapString::new_in(Global);
+    assert!(s.is_empty());
+    assert_eq!(s.delete_backward(), None);
+    s.insert_str("héllo");
+    assert_eq!(s.cursor(), 6);
+
+    s.move_cursor(0);
+    s.insert_str(">> ");
+    assert_eq!(s.cursor(), 3);
+    assert_eq!(s.as_str(), ">> héllo");
+    // Closing the gap doesn't move the cursor
+    assert_eq!(s.cursor(), 3);
+
+    assert_eq!(s.delete_forward(), Some('h'));
+    assert_eq!(s.delete_forward(), Some('é'));
+    s.insert('a');
+    assert_eq!(s.as_slices(), (">> a", "llo"));
+    assert_eq!(s.delete_backward(), Some('a'));
+    assert_eq!(s.delete_backward(), Some(' '));
+    assert_eq!(s.to_string(), ">>llo");
+    assert_eq!(s.len(), 5);
+
+    s.move_cursor(5);
+    assert_eq!(s.delete_forward(), None);
+    s.clear();
+    assert_eq!(s, "");
+}
+
+#[test]
+fn test_cursor_movement() {
+    let mut s = GapString::from_str_in("añ🦀b", Global);
+    assert!(!s.move_right());
+    assert!(s.move_left());
+    assert_eq!(s.cursor(), 7);
+    assert!(s.move_left());
+    assert_eq!(s.cursor(), 3);
+    s.insert('|');
+    assert!(s.move_right());
+    assert!(s.move_right());
+    assert_eq!(s.cursor(), 9);
+    assert_eq!(s, "añ|🦀b");
+
+    s.move_cursor_to_char(1);
+    assert_eq!(s.cursor(), 1);
+    s.move_cursor_to_char(5);
+    assert_eq!(s.cursor(), s.len());
+    while s.move_left() {}
+    assert_eq!(s.cursor(), 0);
+    assert!(!s.is_char_boundary(2));
+    assert!(s.is_char_boundary(s.len()));
+}
+
+#[test]
+#[should_panic]
+fn test_move_cursor_not_char_boundary() {
+    let mut s = GapString::from_str_in("ñ", Global);
+    s.move_cursor(1);
+}
+
+#[test]
+#[should_panic]
+fn test_move_cursor_to_char_out_of_bounds() {
+    let mut s = GapString::from_str_in("ab", Global);
+    s.move_cursor_to_char(3);
+}
+
+#[test]
+fn test_typing_stays_amortized() {
+    let alloc = Counting::default();
+    let mut s = GapString::with_capacity_in(4, alloc.clone());
+    s.insert_str("{}");
+    s.move_left();
+    let n = 10_000u32;
+    for i in 0..n {
+        s.insert(char::from(b'a' + (i % 26) as u8));
+    }
+    let mut expected = std::string::String::from("{");
+    expected.extend((0..n).map(|i| char::from(b'a' + (i % 26) as u8)));
+    expected.push('}');
+    assert_eq!(s, expected.as_str());
+    // Each reallocation at least doubles the buffer, so there are O(log n) of them
+    assert!(alloc.total() <= 1 + n.ilog2() as usize, "{} allocations", alloc.total());
+    assert_eq!(alloc.live(), 1);
+}
+
+#[test]
+fn test_write_and_conversions() {
+    let string = String::from_str_in("x = ;", Global);
+    let mut s = GapString::from(string);
+    s.move_cursor(4);
+    write!(s, "{}", 6 * 7).unwrap();
+    assert_eq!(format!("{}", s), "x = 42;");
+    assert_eq!(format!("{:?}", s), format!("{:?}", "x = 42;"));
+    assert_eq!(format!("{:>9}|{:.3}|{:-^11.5}", s, s, s), "  x = 42;|x =|---x = 4---");
+
+    let back: String<Global> = s.into();
+    assert_eq!(&*back, "x = 42;");
+    assert_eq!(GapString::<Global>::default(), "");
+}
@@ -0,0 +1,99 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::Counting;
+use std::alloc::Global;
+use std::collections::HashMap;
+use std::fmt::Write;
+use string_alloc::{Interner, Symbol};
+
+#[test]
+fn test_intern_and_resolve() {
+    let mut interner = Interner::new_in(Global);
+    assert!(interner.is_empty());
+    assert_eq!(interner.get("a"), None);
+
+    let a = interner.get_or_intern("a");
+    let b = interner.get_or_intern("bé");
+    let empty = interner.get_or_intern("");
+    assert_eq!(interner.get_or_intern("a"), a);
+    assert_eq!(interner.get_or_intern(""), empty);
+    assert_ne!(a, b);
+    assert_eq!(interner.len(), 3);
+
+    assert_eq!(interner.resolve(a), "a");
+    assert_eq!(interner.resolve(b), "bé");
+    assert_eq!(interner.resolve(empty), "");
+    assert_eq!(interner.get("bé"), Some(b));
+    assert_eq!(interner.try_resolve(Symbol::from_u32(3)), None);
+    assert_eq!([a, b, empty].map(Symbol::as_u32), [0, 1, 2]);
+}
+
+#[test]
+#[should_panic]
+fn test_resolve_foreign_symbol() {
+    let mut other = Interner::new_in(Global);
+    let sym = other.get_or_intern("x");
+    Interner::new_in(Global).resolve(sym);
+}
+
+#[test]
+fn test_many_strings() {
+    let alloc = Counting::default();
+    let mut interner = Interner::new_in(alloc.clone());
+    let mut expected = HashMap::new();
+    let mut name = std::string::String::new();
+    for i in 0..50_000 {
+        name.clear();
+        write!(name, "ident_{}", i % 20_000).unwrap();
+        let sym = interner.get_or_intern(&name);
+        assert_eq!(*expected.entry(name.clone()).or_insert(sym), sym);
+    }
+    assert_eq!(interner.len(), 20_000);
+    for (name, sym) in &expected {
+        assert_eq!(interner.resolve(*sym), name);
+        assert_eq!(interner.get(name), Some(*sym));
+    }
+    // A handful of byte chunks plus the span list and hash table, not one allocation per string
+    assert!(alloc.live() < 16, "{} live allocations", alloc.live());
+
+    // Strings larger than a chunk get one of their own
+    let big = "x".repeat(2 * 1024 * 1024);
+    let sym = interner.get_or_intern(&big);
+    assert_eq!(interner.resolve(sym), big);
+    assert_eq!(interner.resolve(Symbol::from_u32(0)), "ident_0");
+
+    drop(interner);
+    assert_eq!(alloc.live(), 0);
+}
+
+#[test]
+fn test_iteration() {
+    let mut interner = Interner::with_capacity_in(4, Global);
+    for s in ["c", "a", "b", "a"] {
+        interner.get_or_intern(s);
+    }
+    let items: Vec<_> = interner.iter().map(|(sym, s)| (sym.as_u32(), s)).collect();
+    assert_eq!(items, [(0, "c"), (1, "a"), (2, "b")]);
+    assert_eq!(interner.iter().len(), 3);
+    assert_eq!(interner.iter().next_back().map(|(_, s)| s), Some("b"));
+    assert_eq!((&interner).into_iter().count(), 3);
+    assert_eq!(
+        format!("{:?}", interner),
+        r#"{Symbol(0): "c", Symbol(1): "a", Symbol(2): "b"}"#
+    );
+
+    let cloned = interner.clone();
+    assert!(cloned.iter().eq(interner.iter()));
+}
+
+#[test]
+fn test_try_get_or_intern() {
+    let alloc = Counting::default();
+    let mut interner = Interner::new_in(alloc.clone());
+    let sym = interner.try_get_or_intern("fallible").unwrap();
+    assert_eq!(interner.try_get_or_intern("fallible").unwrap(), sym);
+    assert_eq!(interner.resolve(sym), "fallible");
+    assert_eq!(interner.allocator().live(), 4);
+}
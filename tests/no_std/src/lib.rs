@@ -0,0 +1,20 @@
+//! Compile-only check that the `format_in!` family expands in a `#![no_std]` crate.
+//!
+//! Nothing in here links `std`, so any macro expansion that reaches for `std::` fails the build.
+
+#![no_std]
+#![feature(allocator_api)]
+
+extern crate alloc;
+
+use alloc::alloc::Global;
+use alloc::collections::TryReserveError;
+use string_alloc::{format_in, try_format_in, String};
+
+pub fn greet(name: &str) -> String<Global> {
+    format_in!(Global, "Hello, {}!", name)
+}
+
+pub fn try_greet(name: &str, count: usize) -> Result<String<Global>, TryReserveError> {
+    try_format_in!(Global, "Hello, {name} x{count}!")
+}
@@ -0,0 +1,196 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::Counting;
+use std::alloc::Global;
+use std::fmt::Write;
+use string_alloc::{Rope, String};
+
+/// Small deterministic generator, so the randomized test needs no extra dependency.
+struct Lcg(u64);
+
+impl Lcg {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % n.max(1)
+    }
+}
+
+// Snapshots must be able to move to other threads
+const _: () = {
+    fn assert_send_sync<T: Send + Sync>() {}
+    let _ = assert_send_sync::<Rope<Global>>;
+};
+
+fn floor_boundary(s: &str, idx: usize) -> usize {
+    s.floor_char_boundary(idx)
+}
+
+#[test]
+fn test_basic_edits() {
+    let mut rope = Rope::new_in(Global);
+    assert!(rope.is_empty());
+    rope.push_str("Hello");
+    rope.insert(5, ", wörld");
+    rope.insert(0, ">> ");
+    assert_eq!(rope, ">> Hello, wörld");
+    assert_eq!(rope.len(), 16);
+    assert_eq!(rope.len_chars(), 15);
+
+    rope.remove(0..3);
+    rope.remove_chars(5..);
+    assert_eq!(rope, "Hello");
+    rope.insert_at_char(5, "!");
+    assert_eq!(rope.to_string(), "Hello!");
+    assert_eq!(
+        format!("{:?}", Rope::from_str_in("a\"b'\n", Global)),
+        format!("{:?}", "a\"b'\n")
+    );
+
+    rope.clear();
+    assert_eq!(rope, "");
+}
+
+#[test]
+fn test_display_padding() {
+    let rope = Rope::from_str_in("ñb", Global);
+    assert_eq!(format!("{:>5}", rope), "   ñb");
+    assert_eq!(format!("{:.1}", rope), "ñ");
+
+    // Flags apply across chunk boundaries
+    let text = "añ🦀b".repeat(1000);
+    let mut rope = Rope::new_in(Global);
+    text.split_inclusive('b').for_each(|piece| rope.push_str(piece));
+    assert!(rope.chunks().count() > 1);
+    assert_eq!(format!("{:.3001}", rope), format!("{:.3001}", text));
+    assert_eq!(format!("{:*^5000.3999}", rope), format!("{:*^5000.3999}", text));
+    assert_eq!(format!("{:<4001}", rope), format!("{:<4001}", text));
+}
+
+#[test]
+fn test_char_offsets() {
+    let text = "añ🦀b".repeat(1000);
+    let rope = Rope::from_str_in(&text, Global);
+    assert_eq!(rope.len_chars(), text.chars().count());
+    for (char_idx, (byte_idx, _)) in text.char_indices().enumerate().step_by(37) {
+        assert_eq!(rope.char_to_byte(char_idx), byte_idx);
+        assert_eq!(rope.byte_to_char(byte_idx), char_idx);
+    }
+    assert_eq!(rope.char_to_byte(rope.len_chars()), text.len());
+    assert_eq!(rope.byte_to_char(text.len()), rope.len_chars());
+    assert!(!rope.is_char_boundary(2));
+
+    let sliced = rope.slice_chars(2..6);
+    assert_eq!(sliced, "🦀bañ");
+    assert!(rope.chars().eq(text.chars()));
+    assert!(rope.chars().rev().eq(text.chars().rev()));
+}
+
+#[test]
+#[should_panic]
+fn test_insert_not_char_boundary() {
+    let mut rope = Rope::from_str_in("ñ", Global);
+    rope.insert(1, "x");
+}
+
+#[test]
+fn test_random_edits_match_string() {
+    let mut rng = Lcg(7);
+    let mut expected = std::string::String::new();
+    let mut rope = Rope::new_in(Global);
+    let pieces = ["a", "bc", "ñ", "🦀🦀", "\n", &"long line ".repeat(150)];
+    for _ in 0..3000 {
+        match rng.below(4) {
+            0 | 1 => {
+                let idx = floor_boundary(&expected, rng.below(expected.len() + 1));
+                let piece = pieces[rng.below(pieces.len())];
+                expected.insert_str(idx, piece);
+                rope.insert(idx, piece);
+            }
+            2 => {
+                let start = floor_boundary(&expected, rng.below(expected.len() + 1));
+                let end = floor_boundary(&expected, start + rng.below(200)).max(start);
+                expected.replace_range(start..end, "");
+                rope.remove(start..end);
+            }
+            _ => {
+                let start = floor_boundary(&expected, rng.below(expected.len() + 1));
+                let end = floor_boundary(&expected, start + rng.below(3000)).max(start);
+                assert_eq!(rope.slice(start..end), &expected[start..end]);
+            }
+        }
+        assert_eq!(rope.len(), expected.len());
+    }
+    assert_eq!(rope, expected.as_str());
+    assert_eq!(rope.len_chars(), expected.chars().count());
+    assert_eq!(rope.chunks().collect::<std::string::String>(), expected);
+    assert!(rope.chunks().all(|chunk| !chunk.is_empty()));
+    assert_eq!(rope.chunks().rev().map(str::len).sum::<usize>(), expected.len());
+}
+
+#[test]
+fn test_large_document() {
+    let line = "the quick brown fox jumps over the lazy dog\n";
+    let mut rope = Rope::from_str_in(&line.repeat(100_000), Global);
+    assert_eq!(rope.len(), line.len() * 100_000);
+
+    // Each edit only copies a path, so this stays fast even on a multi-megabyte document
+    for i in 0..10_000 {
+        let at = (i * 7919 * line.len()) % rope.len();
+        rope.insert(at, "x");
+        rope.remove(at..at + 1);
+    }
+    assert_eq!(rope.len(), line.len() * 100_000);
+    assert_eq!(rope.slice(..line.len()), line);
+}
+
+#[test]
+fn test_clones_share_structure() {
+    let alloc = Counting::default();
+    let mut rope = Rope::from_str_in(&"abcdefgh".repeat(4096), alloc.clone());
+    let before = alloc.live();
+    let snapshot = rope.clone();
+    assert_eq!(alloc.live(), before);
+
+    rope.insert(10, "xyz");
+    assert_eq!(snapshot.len(), 8 * 4096);
+    assert_eq!(rope.len(), 8 * 4096 + 3);
+    assert_eq!(rope.slice(8..15), "abxyzcd");
+    // Only the edited path was copied
+    assert!(alloc.live() - before < 20, "{} new allocations", alloc.live() - before);
+
+    let mut tail = rope.split_off(16);
+    assert_eq!(rope, "abcdefghabxyzcde");
+    tail.append(&rope);
+    assert_eq!(tail.len(), 8 * 4096 + 3);
+
+    drop((rope, snapshot, tail));
+    assert_eq!(alloc.live(), 0);
+}
+
+#[test]
+fn test_snapshot_on_other_thread() {
+    let mut rope = Rope::from_str_in("shared snapshot", Global);
+    let snapshot = rope.clone();
+    let handle = std::thread::spawn(move || snapshot.slice(7..).to_string());
+    rope.insert(0, "edited ");
+    assert_eq!(handle.join().unwrap(), "snapshot");
+    assert_eq!(rope, "edited shared snapshot");
+}
+
+#[test]
+fn test_conversions_and_write() {
+    let s = String::from_str_in("from a string", Global);
+    let mut rope = Rope::from(s);
+    write!(rope, " and {} more", 2).unwrap();
+    assert_eq!(rope, "from a string and 2 more");
+
+    let back: String<Global> = String::from(&rope);
+    assert_eq!(rope, back);
+    assert_eq!(&*String::from(rope.slice(7..13)), "string");
+    assert_eq!(Rope::<Global>::default(), Rope::new_in(Global));
+}
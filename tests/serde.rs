@@ -1,11 +1,11 @@
-#![feature(allocator_api)]
+#![cfg_attr(feature = "serde", feature(allocator_api))]
 
-#[cfg(feature = "serde")]
-use string_alloc::String;
-#[cfg(feature = "serde")]
-use std::alloc::Global;
-#[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")] mod common;
+
+#[cfg(feature = "serde")] use common::Counting;
+#[cfg(feature = "serde")] use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")] use std::alloc::Global;
+#[cfg(feature = "serde")] use string_alloc::String;
 
 #[cfg(feature = "serde")]
 #[test]
@@ -41,4 +41,123 @@ fn test_serde() {
     let serialized = serde_json::to_string(&person).unwrap();
     let deserialized: Person = serde_json::from_str(&serialized).unwrap();
     assert_eq!(person, deserialized);
-} 
\ No newline at end of file
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_deserialize_owned_input() {
+    // Escapes force serde_json to hand over a transient buffer instead of a borrowed slice
+    let escaped: String<Global> = serde_json::from_str(r#""line\none \"quoted\" é""#).unwrap();
+    assert_eq!(&*escaped, "line\none \"quoted\" é");
+
+    // Readers can never lend out borrowed strings
+    let streamed: String<Global> = serde_json::from_reader(r#""streamed 你好""#.as_bytes()).unwrap();
+    assert_eq!(&*streamed, "streamed 你好");
+
+    let owned: String<Global> = serde_json::from_value(serde_json::Value::String("owned".into())).unwrap();
+    assert_eq!(&*owned, "owned");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_deserialize_bytes() {
+    use serde::de::value::{BytesDeserializer, Error};
+
+    let s = String::<Global>::deserialize(BytesDeserializer::<Error>::new("你好".as_bytes())).unwrap();
+    assert_eq!(&*s, "你好");
+
+    let err = String::<Global>::deserialize(BytesDeserializer::<Error>::new(b"\xFF")).unwrap_err();
+    assert!(err.to_string().contains("expected a string"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_string_seed() {
+    use serde::de::DeserializeSeed;
+    use string_alloc::de::StringSeed;
+
+    let alloc = Counting::default();
+    let mut de = serde_json::Deserializer::from_str(r#""in \"my\" arena""#);
+    let s = StringSeed(alloc.clone()).deserialize(&mut de).unwrap();
+    assert_eq!(&*s, "in \"my\" arena");
+    assert_eq!(alloc.live(), 1);
+    drop(s);
+    assert_eq!(alloc.live(), 0);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+#[allow(clippy::mutable_key_type)] // The counter never affects key ordering
+fn test_vec_and_map_seeds() {
+    use serde::de::DeserializeSeed;
+    use string_alloc::de::{MapSeed, VecSeed};
+
+    let alloc = Counting::default();
+    let mut de = serde_json::Deserializer::from_str(r#"["one", "two", "three"]"#);
+    let vec = VecSeed(alloc.clone()).deserialize(&mut de).unwrap();
+    assert_eq!(vec.iter().map(|s| &**s).collect::<Vec<_>>(), ["one", "two", "three"]);
+    // One allocation for the vector and one per element
+    assert_eq!(alloc.live(), 4);
+    drop(vec);
+    assert_eq!(alloc.live(), 0);
+
+    let mut de = serde_json::Deserializer::from_reader(r#"{"b": "2", "a": "1"}"#.as_bytes());
+    let map = MapSeed(alloc.clone()).deserialize(&mut de).unwrap();
+    let entries: Vec<(&str, &str)> = map.iter().map(|(k, v)| (&**k, &**v)).collect();
+    assert_eq!(entries, [("a", "1"), ("b", "2")]);
+    assert!(alloc.live() > 4);
+    drop(map);
+    assert_eq!(alloc.live(), 0);
+
+    let mut de = serde_json::Deserializer::from_str(r#"[1]"#);
+    assert!(VecSeed(alloc).deserialize(&mut de).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_boxed_str() {
+    use serde::de::DeserializeSeed;
+    use string_alloc::de::BoxStrSeed;
+
+    #[derive(Serialize, Deserialize)]
+    struct Frozen {
+        #[serde(with = "string_alloc::de::boxed_str")]
+        name: Box<str, Global>,
+    }
+
+    let frozen: Frozen = serde_json::from_str(r#"{"name":"esc\u00e9"}"#).unwrap();
+    assert_eq!(&*frozen.name, "escé");
+    assert_eq!(serde_json::to_string(&frozen).unwrap(), r#"{"name":"escé"}"#);
+
+    let alloc = Counting::default();
+    let mut de = serde_json::Deserializer::from_str(r#""counted""#);
+    let boxed = BoxStrSeed(alloc.clone()).deserialize(&mut de).unwrap();
+    assert_eq!(&*boxed, "counted");
+    assert_eq!(alloc.live(), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_interner() {
+    use serde::de::DeserializeSeed;
+    use string_alloc::de::InternerSeed;
+    use string_alloc::{Interner, Symbol};
+
+    let mut interner: Interner = Interner::default();
+    let syms = ["b", "a", "c"].map(|s| interner.get_or_intern(s));
+    let json = serde_json::to_string(&interner).unwrap();
+    assert_eq!(json, r#"["b","a","c"]"#);
+    assert_eq!(serde_json::to_string(&syms).unwrap(), "[0,1,2]");
+
+    let restored: Interner = serde_json::from_str(&json).unwrap();
+    let syms: [Symbol; 3] = serde_json::from_str("[0,1,2]").unwrap();
+    assert_eq!(syms.map(|sym| restored.resolve(sym)), ["b", "a", "c"]);
+
+    let alloc = Counting::default();
+    let mut de = serde_json::Deserializer::from_str(r#"["x","y"]"#);
+    let seeded = InternerSeed(alloc.clone()).deserialize(&mut de).unwrap();
+    assert_eq!(seeded.get("y"), Some(Symbol::from_u32(1)));
+
+    let err = serde_json::from_str::<Interner>(r#"["x","x"]"#).unwrap_err();
+    assert!(err.to_string().contains("a string not seen before"), "{}", err);
+}
@@ -0,0 +1,160 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::Counting;
+use std::alloc::Global;
+use std::fmt::Write;
+use string_alloc::{SmallString, String};
+
+#[test]
+fn test_inline_then_spill() {
+    let alloc = Counting::default();
+    let mut s = SmallString::<8, _>::new_in(alloc.clone());
+    s.push_str("abc");
+    s.push('é');
+    assert_eq!(&*s, "abcé");
+    assert_eq!(s.capacity(), 8);
+    assert!(!s.spilled());
+    assert_eq!(alloc.live(), 0);
+
+    s.push_str("🦀🦀");
+    assert!(s.spilled());
+    assert_eq!(&*s, "abcé🦀🦀");
+    assert_eq!(alloc.live(), 1);
+
+    // Shrinking moves the contents back inline and frees the buffer
+    s.truncate(3);
+    s.shrink_to_fit();
+    assert!(!s.spilled());
+    assert_eq!(&*s, "abc");
+    assert_eq!(alloc.live(), 0);
+}
+
+#[test]
+fn test_editing_inline_and_spilled() {
+    for prefix in ["", "a long enough prefix "] {
+        let mut s = SmallString::<16, Global>::from_str_in(prefix, Global);
+        s.push_str("hé!");
+        let base = prefix.len();
+
+        s.insert(base + 1, '-');
+        assert_eq!(&s[base..], "h-é!");
+        assert_eq!(s.remove(base + 2), 'é');
+        assert_eq!(&s[base..], "h-!");
+        assert_eq!(s.pop(), Some('!'));
+
+        s.push_str("a1b2");
+        s.retain(|c| !c.is_ascii_digit());
+        assert_eq!(&s[base..], "h-ab");
+
+        s.make_ascii_uppercase();
+        assert_eq!(&s[base..], "H-AB");
+
+        s.clear();
+        assert!(s.is_empty());
+    }
+}
+
+#[test]
+fn test_insert_spills() {
+    let mut s = SmallString::<4, Global>::from_str_in("abcd", Global);
+    s.insert(2, '中');
+    assert!(s.spilled());
+    assert_eq!(&*s, "ab中cd");
+}
+
+#[test]
+#[should_panic(expected = "is_char_boundary")]
+fn test_insert_not_char_boundary() {
+    let mut s = SmallString::<8, Global>::from_str_in("é", Global);
+    s.insert(1, 'x');
+}
+
+#[test]
+fn test_string_conversions() {
+    let alloc = Counting::default();
+
+    // A spilled string moves into `String` without copying
+    let small = SmallString::<4, _>::from_str_in("longer than four", alloc.clone());
+    let ptr = small.as_ptr();
+    let s: String<Counting> = small.into();
+    assert_eq!(s.as_ptr(), ptr);
+    assert_eq!(alloc.live(), 1);
+
+    let small: SmallString<4, _> = s.into();
+    assert!(small.spilled());
+    assert_eq!(small.as_ptr(), ptr);
+
+    // Inline contents are copied into the allocator on conversion
+    let inline = SmallString::<4, _>::from_str_in("tiny", alloc.clone());
+    let s = inline.into_string();
+    assert_eq!(&*s, "tiny");
+    assert_eq!(alloc.live(), 2);
+}
+
+#[test]
+fn test_traits() {
+    let a = SmallString::<8, Global>::from("same");
+    let b = SmallString::<2, Global>::from_str_in("same", Global);
+    assert_eq!(a, b);
+    assert_eq!(a, "same");
+    assert_eq!(a, String::from_str_in("same", Global));
+    let later = SmallString::from("samf");
+    assert!(a < later);
+    assert_eq!(format!("{:?} {:>5}", a, b), "\"same\"  same");
+
+    let mut w = SmallString::<8, Global>::default();
+    write!(w, "{}+{}={}", 20, 22, 42).unwrap();
+    assert_eq!(&*w, "20+22=42");
+    assert!(!w.spilled());
+    let c = w.clone();
+    assert_eq!(c, w);
+}
+
+#[test]
+fn test_try_reserve() {
+    let mut s = SmallString::<8, Global>::from_str_in("abc", Global);
+    s.try_reserve(5).unwrap();
+    assert!(!s.spilled());
+    assert!(s.try_reserve(usize::MAX).is_err());
+    assert!(!s.spilled());
+    assert_eq!(&*s, "abc");
+    s.try_push_str("0123456789").unwrap();
+    assert!(s.spilled());
+    assert_eq!(&*s, "abc0123456789");
+}
+
+#[test]
+fn test_size_and_drop() {
+    // The inline buffer overlaps the spilled string instead of sitting next to it
+    let word = std::mem::size_of::<usize>();
+    assert_eq!(
+        std::mem::size_of::<SmallString<24>>(),
+        std::mem::size_of::<String>() + word
+    );
+    assert_eq!(
+        std::mem::size_of::<SmallString<8>>(),
+        std::mem::size_of::<String>() + word
+    );
+    assert_eq!(std::mem::size_of::<SmallString<64>>(), 64 + word);
+
+    let alloc = Counting::default();
+    let inline = SmallString::<8, _>::from_str_in("inline", alloc.clone());
+    let spilled = SmallString::<8, _>::from_str_in("spilled past eight", alloc.clone());
+    let copies = (inline.clone(), spilled.clone());
+    assert_eq!(alloc.live(), 2);
+    drop((inline, spilled, copies));
+    assert_eq!(alloc.live(), 0);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let s: SmallString<8, Global> = serde_json::from_str(r#""short""#).unwrap();
+    assert!(!s.spilled());
+    let s: SmallString<8, Global> = serde_json::from_str(r#""esc\"aped and long""#).unwrap();
+    assert!(s.spilled());
+    assert_eq!(&*s, "esc\"aped and long");
+    assert_eq!(serde_json::to_string(&s).unwrap(), r#""esc\"aped and long""#);
+}
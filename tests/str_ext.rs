@@ -0,0 +1,105 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::Counting;
+use std::alloc::Global;
+use string_alloc::{StrExt, StrSliceExt, String};
+
+const SAMPLES: &[&str] = &[
+    "",
+    "hello",
+    "Hello, World!",
+    "ñandú ÀÉÎ straße ǅ",
+    "ΣΑΣ ὈΔΥΣΣΕΎΣ Σ aΣ 'Σ' AΣ.b Σ̈ ΑΣ\u{301}",
+    "tab\tquote\"'\n\u{301}e\u{301}",
+    "🦀 crab 🦀",
+];
+
+#[test]
+fn test_case_mapping_matches_std() {
+    for s in SAMPLES {
+        assert_eq!(&*s.to_lowercase_in(Global), s.to_lowercase(), "{:?}", s);
+        assert_eq!(&*s.to_uppercase_in(Global), s.to_uppercase(), "{:?}", s);
+    }
+}
+
+#[test]
+fn test_escapes_match_std() {
+    for s in SAMPLES {
+        assert_eq!(&*s.escape_debug_in(Global), s.escape_debug().to_string(), "{:?}", s);
+        assert_eq!(&*s.escape_default_in(Global), s.escape_default().to_string(), "{:?}", s);
+    }
+}
+
+#[test]
+fn test_replace_matches_std() {
+    for s in SAMPLES {
+        assert_eq!(&*s.replace_in('o', "0", Global), s.replace('o', "0"));
+        assert_eq!(&*s.replace_in("", "|", Global), s.replace("", "|"));
+        assert_eq!(
+            &*s.replace_in(char::is_whitespace, "", Global),
+            s.replace(char::is_whitespace, "")
+        );
+        for n in 0..3 {
+            assert_eq!(&*s.replacen_in(' ', "_", n, Global), s.replacen(' ', "_", n));
+        }
+    }
+    assert_eq!(&*"banana".replace_in("ana", "o", Global), "bona");
+}
+
+#[test]
+fn test_repeat_matches_std() {
+    for s in ["", "a", "ñ🦀", "abc"] {
+        for n in [0, 1, 2, 3, 7, 64, 100] {
+            assert_eq!(&*s.repeat_in(n, Global), s.repeat(n));
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_repeat_overflow() {
+    "ab".repeat_in(usize::MAX, Global);
+}
+
+#[test]
+fn test_join_and_concat_match_std() {
+    let words = ["alpha", "β", "", "gamma"];
+    assert_eq!(&*words.join_in(", ", Global), words.join(", "));
+    assert_eq!(&*words.concat_in(Global), words.concat());
+    assert_eq!(&*words[..1].join_in(", ", Global), "alpha");
+
+    let empty: [&str; 0] = [];
+    assert_eq!(&*empty.join_in(", ", Global), "");
+
+    let owned = [std::string::String::from("x"), std::string::String::from("y")];
+    assert_eq!(&*owned.join_in("-", Global), "x-y");
+    let ours = [String::from_str_in("x", Global), String::from_str_in("y", Global)];
+    assert_eq!(&*ours.concat_in(Global), "xy");
+}
+
+#[test]
+fn test_results_use_given_allocator() {
+    let alloc = Counting::default();
+    let s = "Some Text".to_lowercase_in(alloc.clone());
+    assert_eq!(&*s, "some text");
+    assert_eq!(alloc.live(), 1);
+    let sigma = "ΟΔΟΣ".to_lowercase_in(alloc.clone());
+    assert_eq!(&*sigma, "οδος");
+    assert_eq!(alloc.live(), 2);
+    drop(sigma);
+
+    let joined = ["a", "b", "c"].join_in("::", alloc.clone());
+    assert_eq!(&*joined, "a::b::c");
+    assert_eq!(joined.capacity(), joined.len());
+    assert_eq!(alloc.live(), 2);
+
+    let repeated = "ab".repeat_in(1000, alloc.clone());
+    assert_eq!(repeated.len(), 2000);
+    assert_eq!(alloc.live(), 3);
+
+    let copied = StrExt::to_string_in("copied", alloc.clone());
+    assert_eq!(&*copied, "copied");
+    assert_eq!(copied.allocator().live(), 4);
+}
@@ -34,6 +34,83 @@ fn test_utf8_conversions() {
     assert_eq!(&*unchecked, "valid utf8");
 }
 
+#[test]
+fn test_from_utf8_error() {
+    let mut invalid = Vec::with_capacity_in(64, Global);
+    invalid.extend_from_slice(b"ab\xFFcd");
+    let err = String::from_utf8_in(invalid).unwrap_err();
+    assert_eq!(err.as_bytes(), b"ab\xFFcd");
+    assert_eq!(err.utf8_error().valid_up_to(), 2);
+    assert_eq!(format!("{}", err), "invalid utf-8 sequence of 1 bytes from index 2");
+
+    // The original buffer comes back with its allocation intact
+    let bytes = err.into_bytes();
+    assert_eq!(bytes, b"ab\xFFcd");
+    assert!(bytes.capacity() >= 64);
+}
+
+#[test]
+fn test_from_utf8_lossy() {
+    let input = b"Hello \xF0\x90\x80World\xFF";
+    let s = String::from_utf8_lossy_in(input, Global);
+    assert_eq!(&*s, StdString::from_utf8_lossy(input));
+    assert_eq!(&*s, "Hello \u{FFFD}World\u{FFFD}");
+
+    let s = String::from_utf8_lossy_in("aé中🦀".as_bytes(), Global);
+    assert_eq!(&*s, "aé中🦀");
+}
+
+#[test]
+fn test_from_utf16() {
+    let music: Vec<u16> = "𝄞music".encode_utf16().collect();
+    let s = String::from_utf16_in(&music, Global).unwrap();
+    assert_eq!(&*s, "𝄞music");
+
+    let invalid = [0xD834, 0x006d, 0x0075, 0xDD1E];
+    assert!(String::from_utf16_in(&invalid, Global).is_err());
+    let lossy = String::from_utf16_lossy_in(&invalid, Global);
+    assert_eq!(&*lossy, StdString::from_utf16_lossy(&invalid));
+    assert_eq!(&*lossy, "\u{FFFD}mu\u{FFFD}");
+}
+
+#[test]
+fn test_from_utf16_le_be() {
+    let le: Vec<u8> = "𝄞mu".encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let be: Vec<u8> = "𝄞mu".encode_utf16().flat_map(u16::to_be_bytes).collect();
+    assert_eq!(&*String::from_utf16le_in(&le, Global).unwrap(), "𝄞mu");
+    assert_eq!(&*String::from_utf16be_in(&be, Global).unwrap(), "𝄞mu");
+
+    // Odd lengths are rejected, or replaced in the lossy variants
+    assert!(String::from_utf16le_in(&le[..le.len() - 1], Global).is_err());
+    assert!(String::from_utf16be_in(&be[..be.len() - 1], Global).is_err());
+    assert_eq!(
+        &*String::from_utf16le_lossy_in(&le[..le.len() - 1], Global),
+        "𝄞m\u{FFFD}"
+    );
+    assert_eq!(
+        &*String::from_utf16be_lossy_in(&be[..be.len() - 1], Global),
+        "𝄞m\u{FFFD}"
+    );
+
+    // Lone surrogates
+    let lone = [0x34, 0xD8, b'a', 0];
+    let err = String::from_utf16le_in(&lone, Global).unwrap_err();
+    assert_eq!(err.to_string(), "invalid utf-16: lone surrogate found");
+    assert_eq!(&*String::from_utf16le_lossy_in(&lone, Global), "\u{FFFD}a");
+}
+
+#[test]
+fn test_try_from_vec() {
+    let valid: Vec<u8, Global> = Vec::from("héllo".as_bytes());
+    let s = String::try_from(valid).unwrap();
+    assert_eq!(&*s, "héllo");
+
+    let invalid: Vec<u8, Global> = vec![b'a', 0xC3];
+    let err = String::try_from(invalid).unwrap_err();
+    assert_eq!(err.utf8_error().error_len(), None);
+    assert_eq!(err.into_bytes(), [b'a', 0xC3]);
+}
+
 #[test]
 fn test_string_manipulation() {
     let mut s = String::from_str_in("hello", Global);
@@ -63,6 +140,95 @@ fn test_string_manipulation() {
     assert_eq!(&*s4, "abc");
 }
 
+#[test]
+fn test_byte_index_semantics() {
+    let mut s = String::from_str_in("aé中🦀", Global);
+
+    // Indices are byte offsets, matching std::string::String
+    s.insert(3, '-');
+    assert_eq!(&*s, "aé-中🦀");
+    assert_eq!(s.remove(4), '中');
+    assert_eq!(&*s, "aé-🦀");
+    s.insert(s.len(), '!');
+    assert_eq!(&*s, "aé-🦀!");
+
+    let right = s.split_off(4);
+    assert_eq!(&*s, "aé-");
+    assert_eq!(&*right, "🦀!");
+
+    s.truncate(10);
+    assert_eq!(&*s, "aé-");
+    s.truncate(1);
+    assert_eq!(&*s, "a");
+
+    let mut std_s = StdString::from("aé中🦀");
+    let mut ours = String::from_str_in("aé中🦀", Global);
+    std_s.insert(6, 'x');
+    ours.insert(6, 'x');
+    assert_eq!(&*ours, std_s.as_str());
+}
+
+#[test]
+#[should_panic(expected = "is_char_boundary")]
+fn test_insert_not_char_boundary() {
+    let mut s = String::from_str_in("é", Global);
+    s.insert(1, 'x');
+}
+
+#[test]
+#[should_panic(expected = "cannot remove a char from the end of a string")]
+fn test_remove_at_end() {
+    let mut s = String::from_str_in("abc", Global);
+    s.remove(3);
+}
+
+#[test]
+#[should_panic(expected = "is_char_boundary")]
+fn test_split_off_not_char_boundary() {
+    let mut s = String::from_str_in("中文", Global);
+    s.split_off(2);
+}
+
+#[test]
+#[should_panic(expected = "is_char_boundary")]
+fn test_truncate_not_char_boundary() {
+    let mut s = String::from_str_in("🦀", Global);
+    s.truncate(1);
+}
+
+#[test]
+fn test_char_index_variants() {
+    let mut s = String::from_str_in("aé中🦀", Global);
+
+    s.insert_at_char(2, '-');
+    assert_eq!(&*s, "aé-中🦀");
+    s.insert_at_char(5, '!');
+    assert_eq!(&*s, "aé-中🦀!");
+    assert_eq!(s.remove_char_at(3), '中');
+    assert_eq!(&*s, "aé-🦀!");
+
+    let right = s.split_off_at_char(3);
+    assert_eq!(&*s, "aé-");
+    assert_eq!(&*right, "🦀!");
+
+    s.truncate_chars(2);
+    assert_eq!(&*s, "aé");
+}
+
+#[test]
+#[should_panic(expected = "removal index (is 2) should be < len (is 2)")]
+fn test_remove_char_at_out_of_bounds() {
+    let mut s = String::from_str_in("中文", Global);
+    s.remove_char_at(2);
+}
+
+#[test]
+#[should_panic(expected = "truncate index (is 3) should be <= len (is 2)")]
+fn test_truncate_chars_out_of_bounds() {
+    let mut s = String::from_str_in("中文", Global);
+    s.truncate_chars(3);
+}
+
 #[test]
 fn test_edge_cases() {
     let mut s = String::from_str_in("", Global);
@@ -154,6 +320,128 @@ fn test_traits_and_deref() {
     assert_eq!(second, "world");
 }
 
+#[test]
+fn test_mutable_access() {
+    let mut s = String::from_str_in("Hello, Wörld", Global);
+
+    // In-place str operations through DerefMut
+    s.make_ascii_uppercase();
+    assert_eq!(&*s, "HELLO, WöRLD");
+    s.as_mut_str().make_ascii_lowercase();
+    assert_eq!(&*s, "hello, wörld");
+
+    let (left, right) = s.split_at_mut(5);
+    left.make_ascii_uppercase();
+    assert_eq!(right, ", wörld");
+    assert_eq!(&*s, "HELLO, wörld");
+
+    // Range indexing
+    assert_eq!(&s[..5], "HELLO");
+    assert_eq!(&s[7..], "wörld");
+    s[7..].make_ascii_uppercase();
+    assert_eq!(&s[..], "HELLO, WöRLD");
+
+    unsafe {
+        let vec = s.as_mut_vec();
+        vec.truncate(5);
+        vec.push(b'!');
+    }
+    assert_eq!(&*s, "HELLO!");
+}
+
+#[test]
+#[should_panic(expected = "byte index 2 is not a char boundary")]
+fn test_index_mut_not_char_boundary() {
+    let mut s = String::from_str_in("aé", Global);
+    s[..2].make_ascii_uppercase();
+}
+
+#[test]
+fn test_raw_parts_and_accessors() {
+    let mut s = String::with_capacity_in(32, Global);
+    s.push_str("raw 🦀");
+    assert_eq!(s.as_str(), "raw 🦀");
+    assert_eq!(s.as_bytes(), "raw 🦀".as_bytes());
+    let _: &Global = s.allocator();
+
+    let (ptr, len, cap, alloc) = s.into_raw_parts_with_alloc();
+    assert_eq!(len, "raw 🦀".len());
+    assert!(cap >= 32);
+    let rebuilt = unsafe { String::from_raw_parts_in(ptr, len, cap, alloc) };
+    assert_eq!(&*rebuilt, "raw 🦀");
+    assert!(rebuilt.capacity() >= 32);
+
+    let bytes = rebuilt.into_bytes();
+    assert_eq!(bytes, "raw 🦀".as_bytes());
+    assert!(bytes.capacity() >= 32);
+}
+
+#[test]
+fn test_smart_pointer_conversions() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    let mut s = String::with_capacity_in(64, Global);
+    s.push_str("frozen ❄");
+    let boxed: Box<str, Global> = s.into_boxed_str();
+    assert_eq!(&*boxed, "frozen ❄");
+
+    // Back to a growable string without copying, now with exact capacity
+    let mut s = String::from(boxed);
+    assert_eq!(s.capacity(), s.len());
+    s.push('!');
+    assert_eq!(&*s, "frozen ❄!");
+
+    let rc: Rc<str, Global> = String::from_str_in("shared", Global).into();
+    let rc2 = Rc::clone(&rc);
+    assert_eq!(&*rc2, "shared");
+
+    let arc: Arc<str, Global> = String::from_str_in("threads", Global).into();
+    let handle = std::thread::spawn(move || arc.len());
+    assert_eq!(handle.join().unwrap(), 7);
+}
+
+#[test]
+fn test_leak() {
+    let s = String::from_str_in("leaked", Global);
+    let leaked: &'static mut str = s.leak();
+    leaked.make_ascii_uppercase();
+    assert_eq!(leaked, "LEAKED");
+}
+
+#[test]
+fn test_drain() {
+    let mut s = String::from_str_in("α is alpha, β is beta", Global);
+    let beta_offset = s.find('β').unwrap();
+    let drained: StdString = s.drain(..beta_offset).collect();
+    assert_eq!(drained, "α is alpha, ");
+    assert_eq!(&*s, "β is beta");
+
+    // Double-ended iteration over multi-byte chars
+    let mut s = String::from_str_in("aé中🦀z", Global);
+    let mut drain = s.drain(1..10);
+    assert_eq!(drain.as_str(), "é中🦀");
+    assert_eq!(drain.next(), Some('é'));
+    assert_eq!(drain.next_back(), Some('🦀'));
+    assert_eq!(drain.as_str(), "中");
+    drop(drain);
+    assert_eq!(&*s, "az");
+
+    // The range is removed even if the iterator is not consumed
+    let mut s = String::from_str_in("hello world", Global);
+    s.drain(5..=5);
+    assert_eq!(&*s, "helloworld");
+    s.drain(..);
+    assert!(s.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "is_char_boundary")]
+fn test_drain_not_char_boundary() {
+    let mut s = String::from_str_in("中文", Global);
+    s.drain(1..);
+}
+
 #[test]
 fn test_utf8_edge_cases() {
     // Test various UTF-8 character lengths
@@ -237,7 +525,7 @@ fn test_complex_operations() {
     // Test multiple splits
     let mut s2 = s.clone();
     let mut right = s2.split_off(5);
-    let right2 = right.split_off(1);
+    let right2 = right.split_off('🦀'.len_utf8());
     assert_eq!(&*s2, "Hello");
     assert_eq!(&*right2, "World");
 
@@ -310,6 +598,222 @@ fn test_workarounds() {
     assert_eq!(&*filtered, "Hello Word");
 }
 
+#[test]
+fn test_retain_matches_std() {
+    let text = "log: user=añ🦀 ip=10.0.0.1\n".repeat(2000);
+    let preds: [fn(char) -> bool; 3] = [|c| !c.is_ascii_digit(), |c| c.is_ascii(), |_| false];
+    for pred in preds {
+        let mut ours = String::from_str_in(&text, Global);
+        let mut std = StdString::from(text.as_str());
+        ours.retain(pred);
+        std.retain(pred);
+        assert_eq!(&*ours, std);
+    }
+
+    let mut s = String::from_str_in("Hello, Wörld!", Global);
+    s.retain_mut(|c| {
+        c.make_ascii_uppercase();
+        *c != 'L'
+    });
+    assert_eq!(&*s, "HEO, WöRD!");
+
+    // Replacements may be shorter than the original
+    let mut s = String::from_str_in("a🦀b🦀", Global);
+    s.retain_mut(|c| {
+        if *c == '🦀' {
+            *c = 'é';
+        }
+        true
+    });
+    assert_eq!(&*s, "aébé");
+
+    // Or longer, in which case the rest of the string is shifted up to make room
+    for text in ["ɐbc", "ɐɐɐ", "xɐ-ɐ", &"ɐa".repeat(1000)] {
+        let mut s = String::from_str_in(text, Global);
+        s.retain_mut(|c| {
+            *c = c.to_uppercase().next().unwrap();
+            *c != '-'
+        });
+        let expected: StdString = text
+            .chars()
+            .flat_map(char::to_uppercase)
+            .filter(|&c| c != '-')
+            .collect();
+        assert_eq!(&*s, expected);
+    }
+
+    let mut s = String::from_str_in("a,b;\tc ñ,", Global);
+    s.retain_ascii(|b| b.is_ascii_alphanumeric());
+    assert_eq!(&*s, "abcñ");
+}
+
+#[test]
+fn test_retain_panic_safety() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut s = String::from_str_in("a1ñ2🦀3b", Global);
+    let mut seen = 0;
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        s.retain(|c| {
+            seen += 1;
+            assert!(seen < 5, "boom");
+            !c.is_ascii_digit()
+        })
+    }));
+    assert!(res.is_err());
+    // Visited chars are filtered, unvisited ones kept, and the string is still valid UTF-8
+    assert_eq!(&*s, "añ🦀3b");
+    assert!(std::str::from_utf8(s.as_bytes()).is_ok());
+
+    // The same holds after replacements have grown the buffer
+    let mut s = String::from_str_in("ɐb-ɐ1ɐ2", Global);
+    let mut seen = 0;
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        s.retain_mut(|c| {
+            seen += 1;
+            assert!(seen < 6, "boom");
+            *c = c.to_uppercase().next().unwrap();
+            *c != '-'
+        })
+    }));
+    assert!(res.is_err());
+    assert_eq!(&*s, "ⱯBⱯ1ɐ2");
+}
+
+#[test]
+fn test_insert_str() {
+    let mut s = String::from_str_in("foobar", Global);
+    s.insert_str(3, "ñ");
+    s.insert_str(0, "<");
+    s.insert_str(s.len(), ">");
+    s.insert_str(2, "");
+    assert_eq!(&*s, "<fooñbar>");
+    s.try_insert_str(6, "🦀").unwrap();
+    assert_eq!(&*s, "<fooñ🦀bar>");
+}
+
+#[test]
+#[should_panic]
+fn test_insert_str_not_char_boundary() {
+    let mut s = String::from_str_in("ñ", Global);
+    s.insert_str(1, "a");
+}
+
+#[test]
+fn test_replace_range() {
+    let mut s = String::from_str_in("Hello, world!", Global);
+    s.replace_range(7..12, "世界");
+    assert_eq!(&*s, "Hello, 世界!");
+
+    let mut s = String::from_str_in("Hello, world!", Global);
+    s.replace_range(7..=11, "世界");
+    assert_eq!(&*s, "Hello, 世界!");
+
+    let mut s = String::from_str_in("12345", Global);
+    s.replace_range(1..2, "");
+    assert_eq!(&*s, "1345");
+    s.replace_range(1..1, "2");
+    assert_eq!(&*s, "12345");
+    s.replace_range(.., "");
+    assert_eq!(&*s, "");
+}
+
+#[test]
+#[should_panic]
+fn test_replace_range_char_boundary() {
+    let mut s = String::from_str_in("Hello, 世界!", Global);
+    s.replace_range(..8, "");
+}
+
+#[test]
+#[should_panic]
+fn test_replace_range_out_of_bounds() {
+    let mut s = String::from_str_in("12345", Global);
+    s.replace_range(5..6, "789");
+}
+
+#[test]
+fn test_extend_from_within() {
+    let mut s = String::from_str_in("abcñ", Global);
+    s.extend_from_within(2..);
+    assert_eq!(&*s, "abcñcñ");
+    s.extend_from_within(..2);
+    assert_eq!(&*s, "abcñcñab");
+    s.try_extend_from_within(3..=4).unwrap();
+    assert_eq!(&*s, "abcñcñabñ");
+}
+
+#[test]
+#[should_panic]
+fn test_extend_from_within_char_boundary() {
+    let mut s = String::from_str_in("ñ", Global);
+    s.extend_from_within(1..);
+}
+
+#[test]
+fn test_remove_matches() {
+    let mut s = String::from_str_in("abc", Global);
+    s.remove_matches('b');
+    assert_eq!(&*s, "ac");
+    s.remove_matches('b');
+    assert_eq!(&*s, "ac");
+
+    let mut s = String::from_str_in("abcb", Global);
+    s.remove_matches('b');
+    assert_eq!(&*s, "ac");
+
+    let mut s = String::from_str_in("ศไทย中华Việt Nam; foobarศ", Global);
+    s.remove_matches('ศ');
+    assert_eq!(&*s, "ไทย中华Việt Nam; foobar");
+
+    let mut s = String::from_str_in("", Global);
+    s.remove_matches("");
+    assert_eq!(&*s, "");
+
+    let mut s = String::from_str_in("aaaaa", Global);
+    s.remove_matches('a');
+    assert_eq!(&*s, "");
+
+    let mut s = String::from_str_in("Hello, world!", Global);
+    s.remove_matches(|c: char| c == 'l' || c == 'o');
+    assert_eq!(&*s, "He, wrd!");
+
+    let mut s = String::from_str_in("banana", Global);
+    s.remove_matches("ana");
+    assert_eq!(&*s, "bna");
+}
+
+#[test]
+fn test_remove_matches_panic_safety() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut s = String::from_str_in("a-ñ-b🦀-c", Global);
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        s.remove_matches(|c: char| {
+            assert_ne!(c, 'b', "boom");
+            c == '-'
+        })
+    }));
+    assert!(res.is_err());
+    // The search runs before any edit, so nothing is removed
+    assert_eq!(&*s, "a-ñ-b🦀-c");
+}
+
+#[test]
+fn test_into_chars() {
+    let s = String::from_str_in("añ🦀z", Global);
+    let mut chars = s.into_chars();
+    assert_eq!(chars.next(), Some('a'));
+    assert_eq!(chars.next_back(), Some('z'));
+    assert_eq!(chars.as_str(), "ñ🦀");
+    assert_eq!(format!("{:?}", chars), r#"IntoChars("ñ🦀")"#);
+    assert_eq!(chars.clone().collect::<StdString>(), "ñ🦀");
+    assert_eq!(chars.next(), Some('ñ'));
+    assert_eq!(chars.next(), Some('🦀'));
+    assert_eq!(chars.next(), None);
+    assert_eq!(chars.next_back(), None);
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn test_std_string_conversions() {
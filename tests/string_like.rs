@@ -0,0 +1,94 @@
+#![feature(allocator_api)]
+
+use std::alloc::Global;
+use string_alloc::{ArrayString, CapacityError, SmallString, String, StringLike};
+
+fn render<S: StringLike>(items: &[(&str, u32)], alloc: S::Alloc) -> S {
+    let mut out = S::new_in(alloc);
+    for (key, n) in items {
+        out.reserve(key.len() + 2);
+        out.push_str(key);
+        out.push('=');
+        out.push(char::from_digit(*n, 10).unwrap());
+        out.push(';');
+    }
+    // Drop the trailing separator
+    if !out.is_empty() {
+        out.truncate(out.len() - 1);
+    }
+    out
+}
+
+const ITEMS: &[(&str, u32)] = &[("ä", 1), ("b", 2), ("c", 3)];
+
+#[test]
+fn test_generic_render() {
+    let s: String<Global> = render(ITEMS, Global);
+    assert_eq!(s.as_str(), "ä=1;b=2;c=3");
+
+    let small: SmallString<4, Global> = render(ITEMS, Global);
+    assert!(small.spilled());
+    assert_eq!(small.as_str(), s.as_str());
+
+    let array: ArrayString<16> = render(ITEMS, ());
+    assert_eq!(array.as_str(), s.as_str());
+    assert_eq!(StringLike::capacity(&array), 16);
+
+    #[cfg(feature = "std")]
+    {
+        let std_s: std::string::String = render(ITEMS, ());
+        assert_eq!(std_s, s.as_str());
+    }
+}
+
+#[test]
+fn test_construction() {
+    fn build<S: StringLike>(alloc: S::Alloc) -> S {
+        let mut s = S::from_str_in("hello", alloc);
+        assert!(StringLike::capacity(&s) >= 5);
+        s.clear();
+        assert!(s.is_empty());
+        s
+    }
+    build::<String<Global>>(Global);
+    build::<SmallString<2, Global>>(Global);
+    build::<ArrayString<5>>(());
+
+    let s = <String<Global> as StringLike>::with_capacity_in(32, Global);
+    assert!(StringLike::capacity(&s) >= 32);
+}
+
+#[test]
+fn test_fallible_push() {
+    fn fill<S: StringLike>(s: &mut S, chunk: &str) -> Result<usize, S::Error> {
+        for n in 0..8 {
+            if let Err(err) = s.try_push_str(chunk) {
+                return Err(err).inspect_err(|_| assert_eq!(s.len(), n * chunk.len()));
+            }
+        }
+        Ok(s.len())
+    }
+
+    let mut s = String::new_in(Global);
+    assert_eq!(fill(&mut s, "ab").unwrap(), 16);
+
+    let mut array = ArrayString::<7>::new();
+    let err: CapacityError = fill(&mut array, "ab").unwrap_err();
+    assert_eq!(err.to_string(), "insufficient capacity");
+    assert_eq!(array.as_str(), "ababab");
+}
+
+#[test]
+#[should_panic]
+fn test_array_push_overflow() {
+    let mut array = ArrayString::<2>::new();
+    StringLike::push_str(&mut array, "abc");
+}
+
+#[test]
+#[should_panic]
+fn test_array_reserve_overflow() {
+    let mut array = ArrayString::<4>::new();
+    StringLike::push_str(&mut array, "ab");
+    StringLike::reserve(&mut array, 3);
+}
@@ -0,0 +1,129 @@
+#![feature(allocator_api)]
+
+use std::alloc::Global;
+use std::string::String as StdString;
+use string_alloc::String;
+
+/// Formats a value with a range of flags, so each impl can be compared against std's output.
+fn formats<T: std::fmt::Display + std::fmt::Debug>(v: &T) -> Vec<StdString> {
+    vec![
+        format!("{}", v),
+        format!("{:>12}", v),
+        format!("{:-<12}", v),
+        format!("{:^12.3}", v),
+        format!("{:.2}", v),
+        format!("{:?}", v),
+        format!("{:>20?}", v),
+        format!("{:#?}", v),
+    ]
+}
+
+#[test]
+fn test_display_and_debug_match_std() {
+    for text in ["", "abc", "héllo wörld", "tab\tquote\"'\n\u{301}", "🦀🦀🦀🦀"] {
+        let ours = String::from_str_in(text, Global);
+        let std = StdString::from(text);
+        assert_eq!(formats(&ours), formats(&std), "{:?}", text);
+    }
+}
+
+#[test]
+fn test_default_and_take() {
+    let mut s: String = String::default();
+    assert!(s.is_empty());
+    s.push_str("taken");
+    let taken = std::mem::take(&mut s);
+    assert_eq!(&*taken, "taken");
+    assert_eq!(&*s, "");
+
+    #[derive(Default)]
+    struct Holder {
+        name: String,
+    }
+    assert!(Holder::default().name.is_empty());
+}
+
+#[test]
+fn test_from_str() {
+    let s: String = "parsed".parse().unwrap();
+    assert_eq!(&*s, "parsed");
+    let std: StdString = "parsed".parse().unwrap();
+    assert_eq!(&*s, std);
+}
+
+#[test]
+fn test_extend_matches_std() {
+    let mut ours = String::from_str_in("x", Global);
+    let mut std = StdString::from("x");
+
+    ours.extend(['a', 'ñ']);
+    std.extend(['a', 'ñ']);
+    ours.extend(&['🦀', 'b']);
+    std.extend(&['🦀', 'b']);
+    ours.extend(["cd", "", "é"]);
+    std.extend(["cd", "", "é"]);
+    ours.extend([String::from_str_in("fg", Global), String::from_str_in("h", Global)]);
+    std.extend([StdString::from("fg"), StdString::from("h")]);
+    assert_eq!(&*ours, std);
+}
+
+#[test]
+fn test_from_iterator_matches_std() {
+    let chars = ['a', 'ñ', '🦀'];
+    let words = ["alpha", "β", "gamma"];
+
+    let ours: String = chars.iter().collect();
+    let std: StdString = chars.iter().collect();
+    assert_eq!(&*ours, std);
+
+    let ours: String = chars.into_iter().rev().collect();
+    let std: StdString = chars.into_iter().rev().collect();
+    assert_eq!(&*ours, std);
+
+    let ours: String = words.into_iter().collect();
+    let std: StdString = words.into_iter().collect();
+    assert_eq!(&*ours, std);
+
+    let ours: String = words.iter().map(|w| String::from_str_in(w, Global)).collect();
+    assert_eq!(&*ours, std);
+
+    let ours: String = "a b c".split(' ').rev().collect();
+    assert_eq!(&*ours, "cba");
+}
+
+#[test]
+fn test_index_ranges_match_std() {
+    let text = "héllo wörld";
+    let ours = String::from_str_in(text, Global);
+    let std = StdString::from(text);
+    assert_eq!(&ours[..], &std[..]);
+    assert_eq!(&ours[1..3], &std[1..3]);
+    assert_eq!(&ours[1..=2], &std[1..=2]);
+    assert_eq!(&ours[3..], &std[3..]);
+    assert_eq!(&ours[..3], &std[..3]);
+    assert_eq!(&ours[..=5], &std[..=5]);
+
+    let mut ours = ours;
+    ours[..1].make_ascii_uppercase();
+    assert_eq!(&*ours, "Héllo wörld");
+}
+
+#[test]
+#[should_panic]
+fn test_index_not_char_boundary() {
+    let s = String::from_str_in("é", Global);
+    let _ = &s[..1];
+}
+
+#[test]
+fn test_add_assign() {
+    let mut ours = String::from_str_in("a", Global);
+    let mut std = StdString::from("a");
+    ours += "bç";
+    std += "bç";
+    ours += "";
+    std += "";
+    assert_eq!(&*ours, std);
+    let ours = ours + "!";
+    assert_eq!(&*ours, std + "!");
+}